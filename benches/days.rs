@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aoc2023::days::DAYS;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+
+// Every day's parse, part 1 and part 2, on the synthetic inputs in
+// `benches/inputs` (written by `aoc2023 generate --seed 1 --size 100 --out
+// benches/inputs`). They are committed rather than generated, so that changes
+// to the generators don't make results incomparable with saved baselines:
+//
+//   cargo bench -- --save-baseline before   # on the old code
+//   cargo bench -- --baseline before        # on the new code
+//   cargo bench -- day17/                   # only some benchmarks
+
+fn input(day: u32) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("benches/inputs")
+        .join(day.to_string());
+    let input =
+        fs::read_to_string(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+    input.trim_matches('\n').to_string()
+}
+
+fn bench_days(c: &mut Criterion) {
+    for day in &DAYS {
+        let input = input(day.number);
+        let mut group = c.benchmark_group(format!("day{}", day.number));
+        // Some parts take a good part of a second, too long for linear sampling.
+        group.sampling_mode(SamplingMode::Flat);
+        group.bench_function("parse", |b| {
+            b.iter(|| (day.parse)(black_box(&input)).unwrap())
+        });
+        for (i, part) in day.parts.iter().enumerate() {
+            group.bench_function(format!("part{}", i + 1), |b| {
+                b.iter(|| part(black_box(&input)).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(2));
+    targets = bench_days
+}
+criterion_main!(benches);
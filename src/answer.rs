@@ -0,0 +1,109 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use num::{BigInt, BigUint};
+
+/// The answer to a single part, independent of the integer type the solver uses.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(BigInt),
+    Unsigned(BigUint),
+    Text(String),
+}
+
+impl Answer {
+    fn as_bigint(&self) -> Option<BigInt> {
+        match self {
+            Answer::Signed(n) => Some(n.clone()),
+            Answer::Unsigned(n) => Some(BigInt::from(n.clone())),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    // Numbers compare by value, so `Signed(5) == Unsigned(5)`.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Text(_), _) | (_, Answer::Text(_)) => false,
+            (a, b) => a.as_bigint() == b.as_bigint(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Parses an expected answer, e.g. from a file of known answers.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ok(if let Ok(n) = s.parse::<BigUint>() {
+            Answer::Unsigned(n)
+        } else if let Ok(n) = s.parse::<BigInt>() {
+            Answer::Signed(n)
+        } else {
+            Answer::Text(s.to_string())
+        })
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $($t:ty),+) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::$variant(value.into())
+                }
+            }
+        )+
+    };
+}
+
+impl_from!(Signed, i8, i16, i32, i64, i128, isize, BigInt);
+impl_from!(Unsigned, u8, u16, u32, u64, u128, usize, BigUint);
+impl_from!(Text, String, &str);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-42i32).to_string(), "-42");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(
+            Answer::from("Merry Christmas").to_string(),
+            "Merry Christmas"
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Answer::from(5i64), Answer::from(5usize));
+        assert_ne!(Answer::from(-5i64), Answer::from(5usize));
+        assert_ne!(Answer::from(5usize), Answer::from("5"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("123".parse::<Answer>().unwrap(), Answer::from(123u32));
+        assert_eq!("-7".parse::<Answer>().unwrap(), Answer::from(-7i32));
+        assert_eq!(
+            "EXAMPLE".parse::<Answer>().unwrap(),
+            Answer::Text("EXAMPLE".to_string())
+        );
+    }
+}
@@ -1,93 +1,60 @@
-use regex::Regex;
-use lazy_static::lazy_static;
+use std::env;
+use std::process::ExitCode;
 
-fn main() {
-    let input = aoc2023::readfile("inputs/2");
-    println!("part1: {}", part1(&input));
-    println!("part2: {}", part2(&input));
-}
-
-fn part1(input: &str) -> i32 {
-    input
-        .split('\n')
-        .map(Game::from)
-        .filter(Game::is_feasible)
-        .map(|game| game.id)
-        .sum()
-}
-
-fn part2(input: &str) -> i32 {
-    input
-        .split('\n')
-        .map(|line| Game::from(line).get_minimum_set_power())
-        .sum()
-}
-
-struct Game {
-    id: i32,
-    sets: Vec<Set>,
-}
+use aoc2023::days::day2::{self, Bag};
 
-lazy_static! {
-    static ref GAME_RE: Regex = Regex::new(r"Game (\d+):(.*)").unwrap();
-}
+const USAGE: &str = "usage: 2                   prints both answers
+       2 --possible BAG    prints the games possible with the bag, e.g. '20 red, 3 purple'
+       2 --minimal         prints the smallest bag of every game";
 
-impl From<&str> for Game {
-    fn from(value: &str) -> Self {
-        let capture = GAME_RE.captures(value).unwrap();
-        let id = aoc2023::parse(capture.get(1).unwrap().as_str());
-        let sets: Vec<Set> = capture
-            .get(2)
-            .unwrap()
-            .as_str()
-            .split(';')
-            .map(Set::from)
-            .collect();
-        Self { id, sets }
-    }
+enum Query {
+    Possible(Bag),
+    Minimal,
 }
 
-impl Game {
-    fn is_feasible(&self) -> bool {
-        self.sets.iter().all(Set::is_feasible)
-    }
-
-    fn get_minimum_set_power(&self) -> i32 {
-        let red = self.sets.iter().map(|set| set.red).max().unwrap();
-        let green = self.sets.iter().map(|set| set.green).max().unwrap();
-        let blue = self.sets.iter().map(|set| set.blue).max().unwrap();
-        red * green * blue
-    }
-}
-
-struct Set {
-    red: i32,
-    green: i32,
-    blue: i32,
-}
-
-lazy_static! {
-    static ref SET_RE: Regex = Regex::new(r"(\d+) (\w+)").unwrap();
-}
-
-impl From<&str> for Set {
-    fn from(value: &str) -> Self {
-        let (mut red, mut green, mut blue) = (0, 0, 0);
-        for capture in SET_RE.captures_iter(value) {
-            let number = aoc2023::parse(capture.get(1).unwrap().as_str());
-            match capture.get(2).unwrap().as_str() {
-                "red" => red = number,
-                "green" => green = number,
-                "blue" => blue = number,
-                _ => panic!("Unknown text {}", capture.get(0).unwrap().as_str()),
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let query = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => return aoc2023::runner::main(2),
+        ["--possible", bag] => match bag.parse() {
+            Ok(bag) => Query::Possible(bag),
+            Err(error) => {
+                eprintln!("bag: {}", error);
+                return ExitCode::FAILURE;
             }
+        },
+        ["--minimal"] => Query::Minimal,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
         }
-        Self { red, green, blue }
-    }
+    };
+    aoc2023::runner::query(2, |input| run(input, &query))
 }
 
-impl Set {
-    fn is_feasible(&self) -> bool {
-        self.red <= 12 && self.green <= 13 && self.blue <= 14
+fn run(input: &str, query: &Query) -> Result<(), Box<dyn std::error::Error>> {
+    let games = day2::parse(input)?;
+    match query {
+        Query::Possible(bag) => {
+            let ids = day2::possible_games(&games, bag).collect::<Vec<_>>();
+            for id in &ids {
+                println!("Game {}", id);
+            }
+            println!(
+                "{} of {} games are possible with {}, the sum of their ids is {}",
+                ids.len(),
+                games.len(),
+                bag,
+                ids.iter().sum::<u32>()
+            );
+        }
+        Query::Minimal => {
+            for game in &games {
+                let bag = game.minimal_bag();
+                let power = bag.power(&["red", "green", "blue"]);
+                println!("Game {}: {} (power {})", game.id, bag, power);
+            }
+        }
     }
+    Ok(())
 }
@@ -0,0 +1,344 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not, Sub, SubAssign};
+
+use crate::{Direction, Point};
+
+// A set of points on a board, one bit per cell. Every row starts at a new
+// word, and bits past the width are always zero, so equal sets are equal grids.
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// The cells of the board for which `f` is true.
+    pub fn from_board<T>(board: &[Vec<T>], f: impl Fn(&T) -> bool) -> Self {
+        let width = board.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut grid = Self::new(width, board.len());
+        for (y, row) in board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if f(cell) {
+                    grid.words[y * grid.stride + x / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, p: &Point) -> Option<(usize, u64)> {
+        let (x, y) = (usize::try_from(p.x).ok()?, usize::try_from(p.y).ok()?);
+        (x < self.width && y < self.height).then(|| (y * self.stride + x / 64, 1 << (x % 64)))
+    }
+
+    pub fn contains(&self, p: &Point) -> bool {
+        self.index(p)
+            .is_some_and(|(i, bit)| self.words[i] & bit != 0)
+    }
+
+    /// Adds a point, returning whether it was new. Points outside of the grid
+    /// are never added.
+    pub fn insert(&mut self, p: Point) -> bool {
+        match self.index(&p) {
+            Some((i, bit)) if self.words[i] & bit == 0 => {
+                self.words[i] |= bit;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a point, returning whether it was there.
+    pub fn remove(&mut self, p: &Point) -> bool {
+        match self.index(p) {
+            Some((i, bit)) if self.words[i] & bit != 0 => {
+                self.words[i] &= !bit;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn extend(&mut self, points: impl IntoIterator<Item = Point>) {
+        for p in points {
+            self.insert(p);
+        }
+    }
+
+    /// The number of points, by popcount.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// The points, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let (y, x0) = (i / self.stride, i % self.stride * 64);
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let x = x0 + word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Point::new(x as i32, y as i32)
+                })
+            })
+        })
+    }
+
+    /// Every point moved one cell in the direction. Points moved off the grid
+    /// are dropped.
+    pub fn shifted(&self, direction: Direction) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        let stride = self.stride;
+        if result.words.is_empty() {
+            return result;
+        }
+        match direction {
+            Direction::Up => {
+                result.words[..stride * (self.height - 1)].copy_from_slice(&self.words[stride..])
+            }
+            Direction::Down => {
+                result.words[stride..].copy_from_slice(&self.words[..stride * (self.height - 1)])
+            }
+            Direction::Left => {
+                for (from, to) in self
+                    .words
+                    .chunks(stride)
+                    .zip(result.words.chunks_mut(stride))
+                {
+                    for i in 0..stride {
+                        let carry = from.get(i + 1).map_or(0, |next| next << 63);
+                        to[i] = from[i] >> 1 | carry;
+                    }
+                }
+            }
+            Direction::Right => {
+                for (from, to) in self
+                    .words
+                    .chunks(stride)
+                    .zip(result.words.chunks_mut(stride))
+                {
+                    for i in 0..stride {
+                        let carry = if i > 0 { from[i - 1] >> 63 } else { 0 };
+                        to[i] = from[i] << 1 | carry;
+                    }
+                }
+                result.clear_padding();
+            }
+        }
+        result
+    }
+
+    /// The points next to any of the points, in the four directions.
+    pub fn neighbours(&self) -> Self {
+        let mut result = self.shifted(Direction::Up);
+        result |= &self.shifted(Direction::Left);
+        result |= &self.shifted(Direction::Down);
+        result |= &self.shifted(Direction::Right);
+        result
+    }
+
+    fn clear_padding(&mut self) {
+        if !self.width.is_multiple_of(64) {
+            let mask = (1 << (self.width % 64)) - 1;
+            for row in self.words.chunks_mut(self.stride) {
+                row[self.stride - 1] &= mask;
+            }
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let mut result = self.clone();
+        result.zip_assign(other, f);
+        result
+    }
+
+    fn zip_assign(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grids of different sizes"
+        );
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a = f(*a, *b);
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+/// The points of the first grid that are not in the second one.
+impl Sub for &BitGrid {
+    type Output = BitGrid;
+
+    fn sub(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a & !b)
+    }
+}
+
+/// Every cell of the grid that is not a point.
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|w| *w = !*w);
+        result.clear_padding();
+        result
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a & b)
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a | b)
+    }
+}
+
+impl SubAssign<&BitGrid> for BitGrid {
+    fn sub_assign(&mut self, other: &BitGrid) {
+        self.zip_assign(other, |a, b| a & !b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn grid(s: &str) -> BitGrid {
+        BitGrid::from_board(&crate::to_board(s), |c| *c == '#')
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut grid = BitGrid::new(70, 2);
+        assert!(grid.insert(Point::new(65, 1)));
+        assert!(!grid.insert(Point::new(65, 1)));
+        assert!(!grid.insert(Point::new(70, 0)));
+        assert!(!grid.insert(Point::new(-1, 0)));
+        assert!(grid.contains(&Point::new(65, 1)));
+        assert!(!grid.contains(&Point::new(1, 65)));
+        assert_eq!(grid.len(), 1);
+        assert!(grid.remove(&Point::new(65, 1)));
+        assert!(!grid.remove(&Point::new(65, 1)));
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut grid = BitGrid::new(130, 3);
+        let points = [(0, 0), (63, 0), (64, 0), (129, 1), (5, 2)].map(|(x, y)| Point::new(x, y));
+        grid.extend(points);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), points);
+    }
+
+    #[test]
+    fn test_shifted() {
+        let g = grid("#..\n.#.\n..#");
+        assert_eq!(g.shifted(Direction::Up), grid(".#.\n..#\n..."));
+        assert_eq!(g.shifted(Direction::Down), grid("...\n#..\n.#."));
+        assert_eq!(g.shifted(Direction::Left), grid("...\n#..\n.#."));
+        assert_eq!(g.shifted(Direction::Right), grid(".#.\n..#\n..."));
+        assert_eq!(g.neighbours(), grid(".#.\n#.#\n.#."));
+    }
+
+    #[test]
+    fn test_shifted_across_words() {
+        // Shifting moves bits between the words of a row, and off its end.
+        let row = |xs: &[usize]| {
+            let mut line = vec!['.'; 100];
+            xs.iter().for_each(|x| line[*x] = '#');
+            grid(&line.iter().collect::<String>())
+        };
+        let g = row(&[0, 63, 64, 99]);
+        assert_eq!(g.shifted(Direction::Right), row(&[1, 64, 65]));
+        assert_eq!(g.shifted(Direction::Left), row(&[62, 63, 98]));
+    }
+
+    #[test]
+    fn test_empty() {
+        let g = BitGrid::new(0, 3);
+        assert_eq!(g.neighbours(), g);
+        assert_eq!(BitGrid::new(3, 0).shifted(Direction::Up).len(), 0);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = grid("##.\n...");
+        let b = grid(".##\n..#");
+        assert_eq!(&a & &b, grid(".#.\n..."));
+        assert_eq!(&a | &b, grid("###\n..#"));
+        assert_eq!(&a ^ &b, grid("#.#\n..#"));
+        assert_eq!(&a - &b, grid("#..\n..."));
+        assert_eq!(!&a, grid("..#\n###"));
+        assert_eq!((!&a).len(), 4);
+    }
+
+    #[test]
+    fn test_matches_hash_set() {
+        let board = crate::to_board("#.#.#\n.....\n##..#");
+        let g = BitGrid::from_board(&board, |c| *c == '#');
+        let set: HashSet<Point> = Point::find_multiple_in_board('#', &board)
+            .into_iter()
+            .collect();
+        assert_eq!(g.iter().collect::<HashSet<_>>(), set);
+        let neighbours: HashSet<Point> = set
+            .iter()
+            .flat_map(|p| [p.up(), p.left(), p.down(), p.right()])
+            .filter(|p| p.is_in_board(&board))
+            .collect();
+        assert_eq!(g.neighbours().iter().collect::<HashSet<_>>(), neighbours);
+    }
+}
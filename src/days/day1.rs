@@ -0,0 +1,299 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+
+/// The digits, as part 1 counts them.
+pub const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+/// The digits spelled out, which part 2 counts too.
+pub const WORDS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+pub fn part1(input: &str) -> error::Result<u32> {
+    sum_calibrations(&parse(input)?, &Scanner::new(&DIGITS))
+}
+
+pub fn part2(input: &str) -> error::Result<u32> {
+    let scanner = Scanner::new(&[&DIGITS[..], &WORDS[..]].concat());
+    sum_calibrations(&parse(input)?, &scanner)
+}
+
+/// The lines of the calibration document, blank ones included.
+pub fn parse(input: &str) -> error::Result<Vec<&str>> {
+    Ok(input.split('\n').collect())
+}
+
+fn sum_calibrations(lines: &[&str], scanner: &Scanner) -> error::Result<u32> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            scanner
+                .calibration(line)
+                .map_err(|error| error.at_line(i + 1, line))
+        })
+        .sum()
+}
+
+/// A token found in a line, by its index in the vocabulary and its byte range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub token: usize,
+    pub value: u32,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds the first and the last tokens of a vocabulary in a line, which may
+/// overlap like "one" and "eight" in "oneight". Tokens are matched by an
+/// Aho-Corasick automaton, and their reversals by another one, so that the
+/// last token is found by scanning from the end.
+pub struct Scanner {
+    tokens: Vec<(String, u32)>,
+    forward: Automaton,
+    backward: Automaton,
+    longest: usize,
+}
+
+impl Scanner {
+    /// A scanner for the tokens, each standing for a digit.
+    pub fn new(vocabulary: &[(&str, u32)]) -> Self {
+        assert!(
+            vocabulary.iter().all(|(token, _)| !token.is_empty()),
+            "empty token"
+        );
+        let tokens = vocabulary
+            .iter()
+            .map(|(token, value)| (token.to_string(), *value))
+            .collect::<Vec<_>>();
+        Self {
+            forward: Automaton::new(tokens.iter().map(|(t, _)| t.bytes().collect())),
+            backward: Automaton::new(tokens.iter().map(|(t, _)| t.bytes().rev().collect())),
+            longest: tokens.iter().map(|(t, _)| t.len()).max().unwrap_or(0),
+            tokens,
+        }
+    }
+
+    fn found(&self, token: usize, start: usize) -> Match {
+        let (text, value) = &self.tokens[token];
+        Match {
+            token,
+            value: *value,
+            start,
+            end: start + text.len(),
+        }
+    }
+
+    /// The token that starts first, the longest one if several do.
+    pub fn first(&self, line: &str) -> Option<Match> {
+        let mut best: Option<Match> = None;
+        let mut state = 0;
+        for (i, byte) in line.bytes().enumerate() {
+            // Tokens ending from here on cannot start before the best one.
+            if best.is_some_and(|best| i >= best.start + self.longest) {
+                break;
+            }
+            state = self.forward.step(state, byte);
+            for token in self.forward.outputs(state) {
+                let found = self.found(*token, i + 1 - self.tokens[*token].0.len());
+                let key = |m: &Match| (m.start, Reverse(m.end));
+                if best.is_none_or(|best| key(&found) < key(&best)) {
+                    best = Some(found);
+                }
+            }
+        }
+        best
+    }
+
+    /// The token that starts last, the longest one if several do.
+    pub fn last(&self, line: &str) -> Option<Match> {
+        let mut state = 0;
+        for (i, byte) in line.bytes().enumerate().rev() {
+            state = self.backward.step(state, byte);
+            // The reversed tokens found here all start at `i`.
+            let longest = self
+                .backward
+                .outputs(state)
+                .iter()
+                .max_by_key(|token| self.tokens[**token].0.len());
+            if let Some(token) = longest {
+                return Some(self.found(*token, i));
+            }
+        }
+        None
+    }
+
+    /// The values of the first and the last token, as a two-digit number.
+    pub fn calibration(&self, line: &str) -> error::Result<u32> {
+        match (self.first(line), self.last(line)) {
+            (Some(first), Some(last)) => Ok(first.value * 10 + last.value),
+            _ => Err(Error::new("no digit")),
+        }
+    }
+}
+
+/// An Aho-Corasick automaton over bytes, with the failure links folded into a
+/// full transition table so that every byte is a single lookup.
+struct Automaton {
+    next: Vec<[u32; 256]>,
+    // The patterns ending at each state, including those ending at its suffixes.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new(patterns: impl Iterator<Item = Vec<u8>>) -> Self {
+        // The trie of the patterns, where 0 is both the root and no edge, as the
+        // root is nobody's child.
+        let mut next = vec![[0; 256]];
+        let mut outputs = vec![vec![]];
+        for (i, pattern) in patterns.enumerate() {
+            let mut state = 0;
+            for byte in pattern {
+                if next[state][byte as usize] == 0 {
+                    next[state][byte as usize] = next.len() as u32;
+                    next.push([0; 256]);
+                    outputs.push(vec![]);
+                }
+                state = next[state][byte as usize] as usize;
+            }
+            outputs[state].push(i);
+        }
+        // Breadth first, so that the longest proper suffix of a state in the
+        // trie, where it fails to, is complete before the state.
+        let mut fail = vec![0; next.len()];
+        let mut queue = next[0]
+            .iter()
+            .filter(|child| **child != 0)
+            .map(|child| *child as usize)
+            .collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            let fallback = next[fail[state]];
+            for (byte, child) in next[state].iter_mut().enumerate() {
+                if *child == 0 {
+                    *child = fallback[byte];
+                } else {
+                    fail[*child as usize] = fallback[byte] as usize;
+                    queue.push_back(*child as usize);
+                }
+            }
+        }
+        Self { next, outputs }
+    }
+
+    fn step(&self, state: usize, byte: u8) -> usize {
+        self.next[state][byte as usize] as usize
+    }
+
+    fn outputs(&self, state: usize) -> &[usize] {
+        &self.outputs[state]
+    }
+}
+
+/// `size` lines, each with at least one digit.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let tokens = 1 + rng.below(6);
+            let digit_token = rng.below(tokens);
+            (0..tokens)
+                .map(|i| match rng.below(3) {
+                    _ if i == digit_token => rng.range(1..10).to_string(),
+                    0 => rng.choose(&WORDS).0.to_string(),
+                    _ => {
+                        let len = 1 + rng.below(4);
+                        rng.word(len)
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        assert_eq!(part1(input), Ok(142));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+        assert_eq!(part2(input), Ok(281));
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let scanner = Scanner::new(&WORDS);
+        let first = scanner.first("xtwone").unwrap();
+        let last = scanner.last("xtwone").unwrap();
+        assert_eq!((first.value, first.start, first.end), (2, 1, 4));
+        assert_eq!((last.value, last.start, last.end), (1, 3, 6));
+        assert_eq!(scanner.calibration("oneight"), Ok(18));
+        assert_eq!(scanner.calibration("eighthree"), Ok(83));
+    }
+
+    #[test]
+    fn test_custom_vocabulary() {
+        let german = [
+            ("null", 0),
+            ("eins", 1),
+            ("zwei", 2),
+            ("drei", 3),
+            ("sieben", 7),
+        ];
+        let scanner = Scanner::new(&german);
+        assert_eq!(scanner.calibration("xnullzweisiebeneins"), Ok(1));
+        assert_eq!(scanner.calibration("zweinsieben"), Ok(27));
+        assert_eq!(scanner.last("dreinsx").unwrap().token, 1);
+        // A token inside a longer one that starts first.
+        let scanner = Scanner::new(&[("bc", 1), ("abcd", 2), ("c", 3)]);
+        assert_eq!(scanner.first("xabcd").unwrap().token, 1);
+        assert_eq!(scanner.first("xabcd").unwrap().start, 1);
+        assert_eq!(scanner.last("xabcd").unwrap().value, 3);
+    }
+
+    #[test]
+    fn test_no_digit() {
+        assert_eq!(
+            part1("a1\nabc\n2").unwrap_err().to_string(),
+            "line 2 'abc': no digit"
+        );
+        assert!(part2("sevenine\nzero").is_err());
+        assert_eq!(part2("sevenine\n0"), Ok(79));
+    }
+}
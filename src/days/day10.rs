@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use crate::error::{self, Error};
+use crate::generate::{self, Rng};
+use crate::render::svg::Svg;
+use crate::render::{Canvas, Color, Scene};
+use crate::{Direction, Point};
+use itertools::Itertools;
+
+pub fn part1(input: &str) -> error::Result<i32> {
+    Ok(get_loop_in_order(&parse(input)?.cells)?.len() as i32 / 2)
+}
+
+pub fn part2(input: &str) -> error::Result<i32> {
+    get_enclosed_size(&parse(input)?.cells)
+}
+
+/// The tiles of the sketch, among which exactly one is the start.
+pub struct Sketch {
+    cells: Vec<Vec<Cell>>,
+}
+
+pub fn parse(input: &str) -> error::Result<Sketch> {
+    let cells = error::parse_grid(input, |tile| match tile {
+        '|' | '-' | 'L' | 'J' | '7' | 'F' | '.' | 'S' => Ok(Cell(tile)),
+        _ => Err(Error::new(format!("invalid tile '{}'", tile))),
+    })?;
+    match Point::find_multiple_in_board(Cell('S'), &cells).len() {
+        0 => Err(Error::new("there is no start 'S'")),
+        1 => Ok(Sketch { cells }),
+        _ => Err(Error::new("there is more than one start 'S'")),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Cell(char);
+
+impl Cell {
+    fn is_connected_up(&self) -> bool {
+        "S|LJ".contains(self.0)
+    }
+    fn is_connected_down(&self) -> bool {
+        "S|F7".contains(self.0)
+    }
+    fn is_connected_left(&self) -> bool {
+        "S-J7".contains(self.0)
+    }
+    fn is_connected_right(&self) -> bool {
+        "S-LF".contains(self.0)
+    }
+    fn is_connected(&self, direction: Direction) -> bool {
+        match direction {
+            Direction::Up => self.is_connected_up(),
+            Direction::Left => self.is_connected_left(),
+            Direction::Down => self.is_connected_down(),
+            Direction::Right => self.is_connected_right(),
+        }
+    }
+}
+
+/// The loop tiles with their distance from the start along the loop.
+fn get_loop(cells: &[Vec<Cell>]) -> error::Result<HashMap<Point, i32>> {
+    let path = get_loop_in_order(cells)?;
+    let distance = |i: usize| i.min(path.len() - i) as i32;
+    Ok(path
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (*p, distance(i)))
+        .collect())
+}
+
+/// The loop tiles in order around the loop, starting at the start. The start
+/// may have more neighbours that connect to it than the two on the loop.
+fn get_loop_in_order(cells: &[Vec<Cell>]) -> error::Result<Vec<Point>> {
+    let start = Point::find_in_board(Cell('S'), cells);
+    connections(cells, start)
+        .into_iter()
+        .find_map(|first| follow_pipes(cells, start, first))
+        .ok_or_else(|| Error::new("the pipes from the start do not form a closed loop"))
+}
+
+/// The tiles from `start` through `first` along the pipes until they get back
+/// to `start`, or `None` if they lead to a dead end.
+fn follow_pipes(cells: &[Vec<Cell>], start: Point, first: Point) -> Option<Vec<Point>> {
+    let mut path = vec![start];
+    let (mut prev, mut point) = (start, first);
+    while point != start {
+        path.push(point);
+        let next = connections(cells, point)
+            .into_iter()
+            .find(|np| *np != prev)?;
+        (prev, point) = (point, next);
+    }
+    Some(path)
+}
+
+/// The neighbours whose pipes connect to the pipe at `point`.
+fn connections(cells: &[Vec<Cell>], point: Point) -> Vec<Point> {
+    let cell = point.get(cells).unwrap();
+    [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ]
+    .into_iter()
+    .filter(|d| {
+        let np = point.step(*d);
+        cell.is_connected(*d) && np.get(cells).is_some_and(|c| c.is_connected(d.opposite()))
+    })
+    .map(|d| point.step(d))
+    .collect()
+}
+
+fn get_enclosed_size(cells: &[Vec<Cell>]) -> error::Result<i32> {
+    let loop_points = get_loop(cells)?.into_keys().collect::<HashSet<_>>();
+    Ok(get_enclosed(cells, &loop_points).len() as i32)
+}
+
+fn get_enclosed(cells: &[Vec<Cell>], loop_points: &HashSet<Point>) -> Vec<Point> {
+    let mut enclosed = vec![];
+    for (y, line) in cells.iter().enumerate() {
+        let mut crossings = vec![];
+        for (x, cell) in line.iter().enumerate() {
+            let p = Point::new(x as i32, y as i32);
+            if loop_points.contains(&p) {
+                crossings.push(*cell);
+            } else if count_crossings_horizontal(&crossings) % 2 == 1 {
+                enclosed.push(p);
+            }
+        }
+    }
+    enclosed
+}
+
+fn count_crossings_horizontal(line: &Vec<Cell>) -> usize {
+    let mut count = 0;
+    let mut line_came_from_up = false;
+    for cell in line {
+        match cell.0 {
+            '|' => count += 1,
+            'L' => line_came_from_up = true,
+            'F' => line_came_from_up = false,
+            '7' if line_came_from_up => count += 1,
+            'J' if !line_came_from_up => count += 1,
+            _ => (),
+        }
+    }
+    count
+}
+
+/// Draws the loop in box-drawing characters, coloured by the distance from
+/// the start, and the enclosed tiles.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let cells = parse(input)?.cells;
+    let distances = get_loop(&cells)?;
+    let loop_points = distances.keys().copied().collect::<HashSet<_>>();
+    let enclosed = get_enclosed(&cells, &loop_points);
+
+    let mut board = crate::to_board(input);
+    for p in &loop_points {
+        let pipe = match p.get(&board).unwrap() {
+            '|' => '│',
+            '-' => '─',
+            'L' => '└',
+            'J' => '┘',
+            '7' => '┐',
+            'F' => '┌',
+            c => c,
+        };
+        p.set(&mut board, pipe);
+    }
+    let scene = Scene::new(&board)
+        .heatmap(distances)
+        .points(enclosed, Color::GREEN);
+    canvas.draw(&scene)
+}
+
+/// Draws the loop through the middle of its tiles, filled.
+pub fn drawing(input: &str) -> error::Result<Svg> {
+    let cells = parse(input)?.cells;
+    let path = get_loop_in_order(&cells)?;
+    Ok(Svg::new().polygon(
+        path.iter().map(|p| (p.x as f64 + 0.5, p.y as f64 + 0.5)),
+        Color::GREEN,
+        Color::YELLOW,
+    ))
+}
+
+/// A loop around a random region of a `size` by `size` grid of 2x2 blocks,
+/// surrounded by junk pipes. The start is on a horizontal pipe.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2) as i64;
+    let cells = (size * size / 2) as usize;
+    let region = generate::grow_region(rng, size, size, (size / 2, size / 2), cells);
+    let corners = generate::outline(&region);
+
+    // Walk the outline at twice the scale, so that no two pipes of the loop
+    // touch unless they are connected.
+    let mut path = vec![];
+    for (i, (x, y)) in corners.iter().enumerate() {
+        let (nx, ny) = corners[(i + 1) % corners.len()];
+        let (dx, dy) = ((nx - x).signum(), (ny - y).signum());
+        let (mut px, mut py) = (2 * x, 2 * y);
+        while (px, py) != (2 * nx, 2 * ny) {
+            path.push(Point::new(px as i32, py as i32));
+            px += dx;
+            py += dy;
+        }
+    }
+
+    let side = 2 * size as usize + 1;
+    let mut board = vec![vec!['.'; side]; side];
+    for (i, p) in path.iter().enumerate() {
+        let neighbors = [
+            path[(i + path.len() - 1) % path.len()],
+            path[(i + 1) % path.len()],
+        ];
+        let pipe = match [p.up(), p.down(), p.left(), p.right()].map(|np| neighbors.contains(&np)) {
+            [true, true, _, _] => '|',
+            [_, _, true, true] => '-',
+            [true, _, true, _] => 'J',
+            [true, _, _, true] => 'L',
+            [_, true, true, _] => '7',
+            _ => 'F',
+        };
+        p.set(&mut board, pipe);
+    }
+    let loop_points = path.iter().collect::<HashSet<_>>();
+    for (y, row) in board.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if !loop_points.contains(&Point::new(x as i32, y as i32)) && rng.chance(0.7) {
+                *cell = *rng.choose(&['|', '-', 'L', 'J', '7', 'F']);
+            }
+        }
+    }
+
+    // Junk around the start must not look connected to it.
+    let horizontal = path
+        .iter()
+        .filter(|p| p.get(&board) == Some('-'))
+        .collect_vec();
+    let start = **rng.choose(&horizontal);
+    start.set(&mut board, 'S');
+    for np in [start.up(), start.down(), start.left(), start.right()] {
+        if !loop_points.contains(&np) {
+            np.set(&mut board, '.');
+        }
+    }
+    board
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1_1() {
+        let input = "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF";
+        assert_eq!(part1(input), Ok(4));
+    }
+
+    #[test]
+    fn test_part1_2() {
+        let input = "7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ";
+        assert_eq!(part1(input), Ok(8));
+    }
+
+    #[test]
+    fn test_part2_1() {
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+        assert_eq!(part2(input), Ok(4));
+    }
+
+    #[test]
+    fn test_part2_2() {
+        let input = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+        assert_eq!(part2(input), Ok(10));
+    }
+
+    #[test]
+    fn test_loop_in_order_skips_dead_ends_at_the_start() {
+        let input = ".....
+.|...
+.S-7.
+.|.|.
+.L-J.";
+        let cells = parse(input).unwrap().cells;
+        let path = get_loop_in_order(&cells).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[..2], [Point::new(1, 2), Point::new(1, 3)]);
+        assert!(drawing(input).is_ok());
+        assert_eq!(part1(input), Ok(4));
+        let open = input.replace('7', ".");
+        assert_eq!(
+            drawing(&open).unwrap_err().message(),
+            "the pipes from the start do not form a closed loop"
+        );
+        assert_eq!(part1(&open).unwrap_err(), drawing(&open).unwrap_err());
+        assert_eq!(part2(&open).unwrap_err(), drawing(&open).unwrap_err());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("S-7\n|.|\nL-X").unwrap_err().to_string(),
+            "line 3 'L-X': invalid tile 'X'"
+        );
+        assert_eq!(
+            part1("F-7\n|.|\nL-J").unwrap_err().message(),
+            "there is no start 'S'"
+        );
+    }
+
+    #[test]
+    fn test_visualize() {
+        let input = "..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+        let mut frames = vec![];
+        visualize(input, &mut frames).unwrap();
+        assert_eq!(frames.len(), 1);
+        let board = &frames[0].board;
+        assert_eq!(board[0].iter().collect::<String>(), "..┌┐.");
+        assert_eq!(board[2].iter().collect::<String>(), "S┘.└┐");
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::Itertools;
+use std::cmp;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    Ok(count_distances_between_all_pairs(&parse(input)?, 1))
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    Ok(count_distances_between_all_pairs(&parse(input)?, 999999))
+}
+
+/// The image, of galaxies `#` in empty space `.`.
+pub fn parse(input: &str) -> error::Result<Vec<Vec<char>>> {
+    error::parse_grid(input, |pixel| match pixel {
+        '.' | '#' => Ok(pixel),
+        _ => Err(Error::new(format!("invalid pixel '{}'", pixel))),
+    })
+}
+
+fn count_distances_between_all_pairs(board: &[Vec<char>], empty_cell_weight: usize) -> usize {
+    let empty_rows = get_empty_rows(board);
+    let empty_cols = get_empty_cols(board);
+    let points = crate::Point::find_multiple_in_board('#', board);
+    let mut result = 0;
+    for i in 0..points.len() {
+        for j in 0..i {
+            let (p1, p2) = (points[i], points[j]);
+            let x1 = cmp::min(p1.x, p2.x) as usize;
+            let x2 = cmp::max(p1.x, p2.x) as usize;
+            let y1 = cmp::min(p1.y, p2.y) as usize;
+            let y2 = cmp::max(p1.y, p2.y) as usize;
+            let d = (x2 - x1) as usize
+                + (y2 - y1) as usize
+                + (empty_cell_weight * empty_rows.iter().filter(|y| **y > y1 && **y < y2).count())
+                + (empty_cell_weight * empty_cols.iter().filter(|x| **x > x1 && **x < x2).count());
+            result += d
+        }
+    }
+    result
+}
+
+fn get_empty_rows(board: &[Vec<char>]) -> Vec<usize> {
+    board
+        .iter()
+        .enumerate()
+        .filter_map(|(y, row)| if row.contains(&'#') { None } else { Some(y) })
+        .collect()
+}
+
+fn get_empty_cols(board: &[Vec<char>]) -> Vec<usize> {
+    (0..board.first().map_or(0, Vec::len))
+        .filter(|x| board.iter().all(|row| row[*x] != '#'))
+        .collect()
+}
+
+/// A `size` by `size` image with about 5% galaxies, and at least two.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let mut image = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(0.05) { '#' } else { '.' })
+                .collect_vec()
+        })
+        .collect_vec();
+    while image.iter().flatten().filter(|c| **c == '#').count() < 2 {
+        image[rng.below(size)][rng.below(size)] = '#';
+    }
+    image
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(374));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(82000210));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("..#\n.x.").unwrap_err().to_string(),
+            "line 2 '.x.': invalid pixel 'x'"
+        );
+        assert_eq!(part1(""), Ok(0));
+    }
+}
@@ -0,0 +1,312 @@
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::enumerate;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    Ok(parse(input)?.into_iter().map(Row::count_arrangements).sum())
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    Ok(parse(input)?
+        .iter()
+        .map(Row::unfolded)
+        .map(Row::count_arrangements)
+        .sum())
+}
+
+/// The condition records, folded as they are written.
+pub fn parse(input: &str) -> error::Result<Vec<Row>> {
+    error::parse_lines(input)
+}
+
+/// Tries every way to fill in the unknown springs, as a reference for `part1`.
+pub fn part1_reference(input: &str) -> usize {
+    input
+        .lines()
+        .map(|line| {
+            let (springs, summary) = line.split_once(' ').unwrap();
+            let unknown = springs.chars().filter(|c| *c == '?').count();
+            (0..1u64 << unknown)
+                .filter(|bits| {
+                    let mut bits = *bits;
+                    let filled = springs
+                        .chars()
+                        .map(|c| match c {
+                            '?' => {
+                                let damaged = bits & 1 == 1;
+                                bits >>= 1;
+                                if damaged {
+                                    '#'
+                                } else {
+                                    '.'
+                                }
+                            }
+                            c => c,
+                        })
+                        .collect::<String>();
+                    let groups = filled
+                        .split('.')
+                        .filter(|group| !group.is_empty())
+                        .map(|group| group.len().to_string())
+                        .collect::<Vec<_>>();
+                    groups.join(",") == summary
+                })
+                .count()
+        })
+        .sum()
+}
+
+/// `part1_reference` of the unfolded rows, only feasible with few unknown springs.
+pub fn part2_reference(input: &str) -> usize {
+    part1_reference(&input.lines().map(unfold).collect::<Vec<_>>().join("\n"))
+}
+
+#[derive(Clone, Copy)]
+enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+impl TryFrom<char> for Spring {
+    type Error = Error;
+
+    fn try_from(value: char) -> error::Result<Self> {
+        match value {
+            '.' => Ok(Self::Operational),
+            '#' => Ok(Self::Damaged),
+            '?' => Ok(Self::Unknown),
+            _ => Err(Error::new(format!("invalid spring '{}'", value))),
+        }
+    }
+}
+
+impl Spring {
+    fn can_be_damaged(&self) -> bool {
+        !matches!(self, Self::Operational)
+    }
+    fn can_be_operational(&self) -> bool {
+        !matches!(self, Self::Damaged)
+    }
+}
+
+pub struct Row {
+    springs: Vec<Spring>,
+    summary: Vec<usize>,
+}
+
+impl FromStr for Row {
+    type Err = Error;
+
+    fn from_str(value: &str) -> error::Result<Self> {
+        let (springs_str, summary_str) = value
+            .split_once(' ')
+            .ok_or_else(|| Error::new("expected the springs and the group sizes"))?;
+        let springs = springs_str
+            .chars()
+            .map(Spring::try_from)
+            .collect::<error::Result<_>>()?;
+        let summary = summary_str
+            .split(',')
+            .map(error::parse)
+            .collect::<error::Result<Vec<usize>>>()?;
+        if summary.contains(&0) {
+            return Err(Error::new("the groups of damaged springs cannot be empty"));
+        }
+        Ok(Self { springs, summary })
+    }
+}
+
+impl Row {
+    /// The row repeated five times, with unknown springs in between.
+    fn unfolded(&self) -> Self {
+        let mut springs = self.springs.clone();
+        for _ in 0..4 {
+            springs.push(Spring::Unknown);
+            springs.extend(&self.springs);
+        }
+        Self {
+            springs,
+            summary: self.summary.repeat(5),
+        }
+    }
+
+    fn count_arrangements(self) -> usize {
+        let mut arrangements = vec![vec![0; self.springs.len() + 1]; self.summary.len() + 1];
+
+        arrangements[0][0] = 1;
+        for (i, spring) in enumerate(&self.springs) {
+            if let Spring::Damaged = spring {
+                break;
+            }
+            arrangements[0][i + 1] = 1;
+        }
+
+        for (summary_index, item_length) in enumerate(&self.summary) {
+            let summary_length = *item_length;
+            for (spring_index, spring) in enumerate(&self.springs) {
+                let arrangements_with_spring_operational = if spring.can_be_operational() {
+                    arrangements[summary_index + 1][spring_index]
+                } else {
+                    0
+                };
+                let arrangements_with_spring_damaged_at_start = if summary_index == 0
+                    && spring_index + 1 == summary_length
+                    && self.springs[0..=spring_index]
+                        .iter()
+                        .all(Spring::can_be_damaged)
+                {
+                    arrangements[summary_index][0]
+                } else {
+                    0
+                };
+                let arrangements_with_spring_damaged = if spring_index + 1 > summary_length
+                    && self.springs[spring_index - summary_length].can_be_operational()
+                    && self.springs[spring_index + 1 - summary_length..=spring_index]
+                        .iter()
+                        .all(Spring::can_be_damaged)
+                {
+                    arrangements[summary_index][spring_index - summary_length]
+                } else {
+                    0
+                };
+                arrangements[summary_index + 1][spring_index + 1] =
+                    arrangements_with_spring_operational
+                        + arrangements_with_spring_damaged_at_start
+                        + arrangements_with_spring_damaged;
+            }
+        }
+        arrangements
+            .iter()
+            .for_each(|row| crate::trace!("{:?}", row));
+        arrangements[self.summary.len()][self.springs.len()]
+    }
+}
+
+fn unfold(s: &str) -> String {
+    let (springs, summary) = s.split_once(' ').unwrap();
+    format!(
+        "{}?{}?{}?{}?{} {},{},{},{},{}",
+        springs, springs, springs, springs, springs, summary, summary, summary, summary, summary
+    )
+}
+
+/// `size` rows of up to 20 springs, each with at least one damaged spring.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let len = 4 + rng.below(17);
+            let mut springs = (0..len)
+                .map(|_| if rng.chance(0.4) { '#' } else { '.' })
+                .collect::<Vec<_>>();
+            if !springs.contains(&'#') {
+                springs[rng.below(len)] = '#';
+            }
+            let summary = springs
+                .split(|c| *c == '.')
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            for spring in springs.iter_mut() {
+                if rng.chance(0.4) {
+                    *spring = '?';
+                }
+            }
+            format!("{} {}", springs.iter().collect::<String>(), summary)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential;
+
+    const INPUT: &str = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(21));
+    }
+
+    #[test]
+    fn test_part1_2() {
+        assert_eq!(part1("#?#????????.?#. 4,1,2,1"), Ok(6));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(525152));
+    }
+
+    #[test]
+    fn test_invalid_rows() {
+        let error = part1("???.### 1,1,3\n.?x 1").unwrap_err();
+        assert_eq!(error.to_string(), "line 2 '.?x 1': invalid spring 'x'");
+        let error = part1("???.###").unwrap_err();
+        assert_eq!(error.message(), "expected the springs and the group sizes");
+        let error = part2("???.### 1,0").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "the groups of damaged springs cannot be empty"
+        );
+    }
+
+    /// Only keeps the rows of `input` that brute force can handle.
+    fn with_few_unknowns(input: &str, max_unknowns: usize) -> String {
+        input
+            .lines()
+            .filter(|line| line.matches('?').count() <= max_unknowns)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_part1_matches_reference() {
+        differential::compare(
+            12,
+            0..50,
+            1..=5,
+            |input| part1(&with_few_unknowns(input, 10)).unwrap(),
+            |input| part1_reference(&with_few_unknowns(input, 10)),
+        )
+        .unwrap_or_else(|mismatch| panic!("{}", mismatch));
+    }
+
+    /// A row of up to 6 springs with one or two unknown, whose springs are
+    /// short enough to brute force once unfolded.
+    fn small_row(rng: &mut Rng) -> String {
+        let len = 1 + rng.below(6);
+        let mut springs = (0..len)
+            .map(|_| if rng.chance(0.5) { '#' } else { '.' })
+            .collect::<Vec<_>>();
+        springs[rng.below(len)] = '#';
+        let summary = springs
+            .split(|c| *c == '.')
+            .filter(|group| !group.is_empty())
+            .map(|group| group.len().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        for _ in 0..1 + rng.below(2) {
+            springs[rng.below(len)] = '?';
+        }
+        format!("{} {}", springs.iter().collect::<String>(), summary)
+    }
+
+    #[test]
+    fn test_part2_matches_reference() {
+        for seed in 0..300 {
+            let row = small_row(&mut Rng::new(seed));
+            assert_eq!(part2(&row), Ok(part2_reference(&row)), "{}", row);
+        }
+    }
+}
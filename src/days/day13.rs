@@ -0,0 +1,242 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::{enumerate, Itertools};
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    parse(input)?.iter().enumerate().map(summarize).sum()
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    parse(input)?.iter().enumerate().map(summarize2).sum()
+}
+
+/// A pattern of ash `.` and rocks `#`, and the line of the input it starts on.
+pub struct Pattern {
+    rows: Vec<Vec<char>>,
+    first: usize,
+}
+
+impl Pattern {
+    /// The error for the `i`th pattern, pointing at its first line.
+    fn error(&self, i: usize, message: &str) -> Error {
+        let line = self.rows[0].iter().collect::<String>();
+        Error::new(format!("{} in pattern {}", message, i + 1)).at_line(self.first, &line)
+    }
+}
+
+/// The patterns, separated by blank lines.
+pub fn parse(input: &str) -> error::Result<Vec<Pattern>> {
+    let mut first = 1;
+    input
+        .split("\n\n")
+        .map(|pattern| {
+            let rows = error::parse_grid_from(pattern, first, |tile| match tile {
+                '.' | '#' => Ok(tile),
+                _ => Err(Error::new(format!("invalid tile '{}'", tile))),
+            })?;
+            if rows.is_empty() {
+                return Err(Error::new("expected a pattern").at_line(first, pattern));
+            }
+            let pattern = Pattern { rows, first };
+            first += pattern.rows.len() + 1;
+            Ok(pattern)
+        })
+        .collect()
+}
+
+fn summarize((n, pattern): (usize, &Pattern)) -> error::Result<usize> {
+    let rows = &pattern.rows;
+    let columns = get_columns(rows);
+
+    for i in 1..rows.len() {
+        if iters_match(rows[i..rows.len()].iter(), rows[0..i].iter().rev()) {
+            return Ok(i * 100);
+        }
+    }
+    for i in 1..columns.len() {
+        if iters_match(columns[i..columns.len()].iter(), columns[0..i].iter().rev()) {
+            return Ok(i);
+        }
+    }
+    Err(pattern.error(n, "no reflection"))
+}
+fn summarize2((n, pattern): (usize, &Pattern)) -> error::Result<usize> {
+    let rows = &pattern.rows;
+    let columns = get_columns(rows);
+
+    for i in 1..rows.len() {
+        if rows[i..rows.len()]
+            .iter()
+            .zip(rows[0..i].iter().rev())
+            .map(|(a, b)| count_diffs(a, b))
+            .sum::<usize>()
+            == 1
+        {
+            return Ok(i * 100);
+        }
+    }
+    for i in 1..columns.len() {
+        if columns[i..columns.len()]
+            .iter()
+            .zip(columns[0..i].iter().rev())
+            .map(|(a, b)| count_diffs(a, b))
+            .sum::<usize>()
+            == 1
+        {
+            return Ok(i);
+        }
+    }
+    Err(pattern.error(n, "no reflection with one smudge"))
+}
+
+fn get_columns(rows: &[Vec<char>]) -> Vec<Vec<char>> {
+    let mut columns = vec![vec![]; rows[0].len()];
+    for row in rows {
+        for (j, cell) in enumerate(row) {
+            columns[j].push(*cell);
+        }
+    }
+    columns
+}
+
+fn iters_match<T, U>(mut a: T, mut b: U) -> bool
+where
+    T: Iterator,
+    U: Iterator,
+    T::Item: PartialEq<U::Item>,
+{
+    while let (Some(aa), Some(bb)) = (a.next(), b.next()) {
+        if aa != bb {
+            return false;
+        }
+    }
+    true
+}
+
+fn count_diffs(a: &[char], b: &[char]) -> usize {
+    a.iter().zip(b).filter(|(aa, bb)| **aa != **bb).count()
+}
+
+/// `size` patterns, each with exactly one line of reflection and exactly one
+/// other line that becomes one after fixing a single smudge.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| generate_pattern(rng))
+        .collect_vec()
+        .join("\n\n")
+}
+
+fn generate_pattern(rng: &mut Rng) -> String {
+    loop {
+        // An odd height leaves some rows unmirrored by any line between rows.
+        let height = 5 + 2 * rng.below(6);
+        let width = 5 + 2 * rng.below(6);
+        let mut rows = (0..height)
+            .map(|_| (0..width).map(|_| *rng.choose(&['#', '.'])).collect_vec())
+            .collect_vec();
+
+        // Mirror the rows around one line and the columns around another,
+        // then smudge a mirrored column on a row that is not mirrored.
+        let row_line = 1 + rng.below(height - 1);
+        let mirrored_rows = row_line.min(height - row_line);
+        for i in 0..mirrored_rows {
+            rows[row_line + i] = rows[row_line - 1 - i].clone();
+        }
+        let column_line = 1 + rng.below(width - 1);
+        let mirrored_columns = column_line.min(width - column_line);
+        for row in rows.iter_mut() {
+            for i in 0..mirrored_columns {
+                row[column_line + i] = row[column_line - 1 - i];
+            }
+        }
+        let unmirrored_rows = (0..height)
+            .filter(|y| *y + mirrored_rows < row_line || *y >= row_line + mirrored_rows)
+            .collect_vec();
+        let y = *rng.choose(&unmirrored_rows);
+        let x = column_line + rng.below(mirrored_columns);
+        rows[y][x] = if rows[y][x] == '#' { '.' } else { '#' };
+        if rng.chance(0.5) {
+            rows = get_columns(&rows);
+        }
+
+        let diffs = reflection_diffs(&rows);
+        if diffs.iter().filter(|d| **d == 0).count() == 1
+            && diffs.iter().filter(|d| **d == 1).count() == 1
+        {
+            return rows
+                .iter()
+                .map(|row| row.iter().collect::<String>())
+                .join("\n");
+        }
+    }
+}
+
+/// The number of differences around every line between rows, then every line between columns.
+fn reflection_diffs(rows: &[Vec<char>]) -> Vec<usize> {
+    let columns = get_columns(rows);
+    [rows, &columns]
+        .iter()
+        .flat_map(|lines| {
+            (1..lines.len()).map(|i| {
+                lines[i..]
+                    .iter()
+                    .zip(lines[..i].iter().rev())
+                    .map(|(a, b)| count_diffs(a, b))
+                    .sum()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(405));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(400));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let input = INPUT.replace("#####.##.\n#####", "#####.##.\n##x##");
+        assert_eq!(
+            part1(&input).unwrap_err().to_string(),
+            "line 13 '##x##.##.': invalid tile 'x'"
+        );
+        assert_eq!(
+            part1("#.\n.#\n\n").unwrap_err().message(),
+            "expected a pattern"
+        );
+        let input = format!("{}\n\n.#.\n#.#", INPUT);
+        assert_eq!(
+            part1(&input).unwrap_err().to_string(),
+            "line 17 '.#.': no reflection in pattern 3"
+        );
+        assert_eq!(
+            part2(&input).unwrap_err().to_string(),
+            "line 17 '.#.': no reflection with one smudge in pattern 3"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::io;
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::{Canvas, Color, Frames, Scene};
+use crate::{BitGrid, Direction};
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let platform = parse(input)?;
+    Ok(get_total_load(
+        &platform.tilt(&platform.rocks, Direction::Up),
+    ))
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let platform = parse(input)?;
+    let (rocks, repeated) = platform.spin(|_, _| ());
+    let goal = 1_000_000_000;
+    let rocks = if goal < rocks.len() {
+        &rocks[goal]
+    } else {
+        &rocks[repeated + (goal - repeated) % (rocks.len() - repeated)]
+    };
+    Ok(get_total_load(rocks))
+}
+
+pub fn parse(input: &str) -> error::Result<Platform> {
+    input.parse()
+}
+
+/// The cube-shaped rocks, which never move, and the rounded rocks.
+pub struct Platform {
+    walls: BitGrid,
+    rocks: BitGrid,
+}
+
+impl FromStr for Platform {
+    type Err = Error;
+
+    fn from_str(input: &str) -> error::Result<Self> {
+        let board = error::parse_grid(input, |c| match c {
+            '.' | '#' | 'O' => Ok(c),
+            _ => Err(Error::new(format!("invalid tile '{}'", c))),
+        })?;
+        Ok(Self {
+            walls: BitGrid::from_board(&board, |c| *c == '#'),
+            rocks: BitGrid::from_board(&board, |c| *c == 'O'),
+        })
+    }
+}
+
+impl Platform {
+    /// Moves all the rocks that can move one tile at a time, until none can.
+    fn tilt(&self, rocks: &BitGrid, direction: Direction) -> BitGrid {
+        let mut rocks = rocks.clone();
+        loop {
+            let free = !&(&rocks | &self.walls);
+            let moved = &rocks.shifted(direction) & &free;
+            if moved.is_empty() {
+                return rocks;
+            }
+            rocks -= &moved.shifted(direction.opposite());
+            rocks |= &moved;
+        }
+    }
+
+    /// Runs spin cycles until the rocks are back where they were after an
+    /// earlier cycle, calling `tilted` with the rocks before and after every
+    /// tilt. Returns the rocks after every cycle until then, starting with
+    /// those before the first, and the cycle after which they repeat.
+    fn spin(&self, mut tilted: impl FnMut(&BitGrid, &BitGrid)) -> (Vec<BitGrid>, usize) {
+        let mut rocks = self.rocks.clone();
+        let mut seen = HashMap::new();
+        let mut cycles = vec![];
+        while !seen.contains_key(&rocks) {
+            seen.insert(rocks.clone(), cycles.len());
+            cycles.push(rocks.clone());
+            for direction in CYCLE {
+                let before = rocks;
+                rocks = self.tilt(&before, direction);
+                tilted(&before, &rocks);
+            }
+        }
+        (cycles, seen[&rocks])
+    }
+
+    fn to_board(&self, rocks: &BitGrid) -> Vec<Vec<char>> {
+        let mut board = vec![vec!['.'; self.walls.width()]; self.walls.height()];
+        self.walls.iter().for_each(|p| p.set(&mut board, '#'));
+        rocks.iter().for_each(|p| p.set(&mut board, 'O'));
+        board
+    }
+}
+
+const CYCLE: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+fn get_total_load(rocks: &BitGrid) -> usize {
+    rocks.iter().map(|p| rocks.height() - p.y as usize).sum()
+}
+
+/// Draws the platform after every tilt, until the cycles repeat. Rocks on
+/// tiles that were empty before the tilt are highlighted.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let platform = parse(input)?;
+    let mut frames = Frames::new(canvas);
+    frames.draw(|| scene(&platform, &platform.rocks, &platform.rocks));
+    platform.spin(|before, rocks| frames.draw(|| scene(&platform, before, rocks)));
+    frames.result()
+}
+
+fn scene(platform: &Platform, before: &BitGrid, rocks: &BitGrid) -> Scene {
+    Scene::new(&platform.to_board(rocks))
+        .points(platform.walls.iter(), Color::GREY)
+        .points((rocks & before).iter(), Color::BLUE)
+        .points((rocks - before).iter(), Color::YELLOW)
+}
+
+/// A `size` by `size` platform.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| match rng.below(20) {
+                    0..=3 => 'O',
+                    4..=6 => '#',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(136));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(64));
+    }
+
+    #[test]
+    fn test_invalid_platforms() {
+        let error = part1("O..\n.x.").unwrap_err();
+        assert_eq!(error.to_string(), "line 2 '.x.': invalid tile 'x'");
+        let error = part2("O..\n.#\n...").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 '.#': the row is not as wide as the first"
+        );
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut frames = vec![];
+        visualize(INPUT, &mut frames).unwrap();
+        // The example repeats from the 3rd cycle on, every 7 cycles.
+        assert_eq!(frames.len(), 1 + 4 * 10);
+        assert_eq!(frames[4 * 10].board, frames[4 * 3].board);
+    }
+}
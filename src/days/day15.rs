@@ -0,0 +1,160 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::enumerate;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    Ok(parse(input)?.iter().map(|step| hash(step.text)).sum())
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let mut boxes = Boxes::new();
+    parse(input)?.iter().for_each(|step| boxes.apply(step));
+    Ok(boxes.get_power())
+}
+
+/// The steps of the initialization sequence, separated by commas.
+pub fn parse(input: &str) -> error::Result<Vec<Step<'_>>> {
+    input.split(',').map(Step::parse).collect()
+}
+
+pub struct Step<'a> {
+    /// The whole step, which part 1 hashes.
+    pub text: &'a str,
+    pub label: &'a str,
+    /// The focal length of the lens to put in the box, or `None` to take the
+    /// lens out.
+    pub focus: Option<usize>,
+}
+
+impl<'a> Step<'a> {
+    fn parse(text: &'a str) -> error::Result<Self> {
+        let (label, focus) = match text.strip_suffix('-') {
+            Some(label) => (label, None),
+            None => {
+                let (label, focus) = text.split_once('=').ok_or_else(|| {
+                    Error::new(format!("expected '-' or '=' at the end of step '{}'", text))
+                })?;
+                (label, Some(error::parse(focus)?))
+            }
+        };
+        Ok(Self { text, label, focus })
+    }
+}
+
+fn hash(s: &str) -> usize {
+    s.chars()
+        .fold(0, |acc, c| ((acc + (c as usize)) * 17) % 256)
+}
+
+struct Boxes<'a>(Vec<Box<'a>>);
+
+impl<'a> Boxes<'a> {
+    fn new() -> Self {
+        Self(vec![Box::new(); 256])
+    }
+
+    fn apply(&mut self, step: &Step<'a>) {
+        match step.focus {
+            Some(focus) => self.0[hash(step.label)].set(step.label, focus),
+            None => self.0[hash(step.label)].drop(step.label),
+        }
+    }
+
+    fn get_power(&self) -> usize {
+        enumerate(&self.0)
+            .map(|(i, bx)| (i + 1) * bx.get_power())
+            .sum()
+    }
+}
+
+#[derive(Clone, Default)]
+struct Box<'a>(Vec<Lens<'a>>);
+
+impl<'a> Box<'a> {
+    fn new() -> Self {
+        Self(vec![])
+    }
+
+    fn set(&mut self, label: &'a str, focus: usize) {
+        match self.0.iter_mut().find(|lens| lens.label == label) {
+            Some(lens) => lens.focus = focus,
+            None => self.0.push(Lens::new(label, focus)),
+        }
+    }
+
+    fn drop(&mut self, label: &str) {
+        for index in 0..self.0.len() {
+            if self.0[index].label == label {
+                self.0.remove(index);
+                break;
+            }
+        }
+    }
+
+    fn get_power(&self) -> usize {
+        enumerate(&self.0)
+            .map(|(i, lens)| (i + 1) * lens.focus)
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Lens<'a> {
+    pub label: &'a str,
+    pub focus: usize,
+}
+
+impl<'a> Lens<'a> {
+    fn new(label: &'a str, focus: usize) -> Self {
+        Self { label, focus }
+    }
+}
+/// `size` steps, on about a third as many labels.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let labels = (0..size / 3 + 1)
+        .map(|_| {
+            let len = 2 + rng.below(5);
+            rng.word(len)
+        })
+        .collect::<Vec<_>>();
+    (0..size.max(1))
+        .map(|_| {
+            let label = rng.choose(&labels);
+            if rng.chance(0.3) {
+                format!("{}-", label)
+            } else {
+                format!("{}={}", label, rng.range(1..10))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(1320));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(145));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("rn=1,cm").unwrap_err().message(),
+            "expected '-' or '=' at the end of step 'cm'"
+        );
+        assert_eq!(
+            part2("rn=x").unwrap_err().message(),
+            "cannot parse 'x': invalid digit found in string"
+        );
+    }
+}
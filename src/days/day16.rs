@@ -0,0 +1,243 @@
+use std::io;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::{Canvas, Color, Frames, Scene};
+use crate::{BitGrid, Direction, Point};
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let board = parse(input)?;
+
+    Ok(count_energized_tiles(
+        &board,
+        Beam::new(Point { x: 0, y: 0 }, Direction::Right),
+    ))
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let board = parse(input)?;
+    let height = board.len() as i32;
+    let width = board.first().map_or(0, Vec::len) as i32;
+
+    Ok((0..width)
+        .map(|x| Beam::new(Point { x, y: 0 }, Direction::Down))
+        .chain((0..height).map(|y| Beam::new(Point { x: 0, y }, Direction::Right)))
+        .chain((0..width).map(|x| Beam::new(Point { x, y: height - 1 }, Direction::Up)))
+        .chain((0..height).map(|y| Beam::new(Point { x: width - 1, y }, Direction::Left)))
+        .map(|beam| count_energized_tiles(&board, beam))
+        .max()
+        .unwrap_or(0))
+}
+
+pub fn parse(input: &str) -> error::Result<Vec<Vec<Tile>>> {
+    error::parse_grid(input, Tile::try_from)
+}
+
+#[derive(Clone, Copy)]
+pub enum Tile {
+    Empty,
+    /// `/`
+    Mirror,
+    /// `\`
+    BackMirror,
+    /// `|`
+    VerticalSplitter,
+    /// `-`
+    HorizontalSplitter,
+}
+
+impl TryFrom<char> for Tile {
+    type Error = Error;
+
+    fn try_from(value: char) -> error::Result<Self> {
+        match value {
+            '.' => Ok(Self::Empty),
+            '/' => Ok(Self::Mirror),
+            '\\' => Ok(Self::BackMirror),
+            '|' => Ok(Self::VerticalSplitter),
+            '-' => Ok(Self::HorizontalSplitter),
+            _ => Err(Error::new(format!("invalid tile '{}'", value))),
+        }
+    }
+}
+
+fn count_energized_tiles(board: &[Vec<Tile>], initial_beam: Beam) -> usize {
+    energized_tiles(board, initial_beam, |_| ()).len()
+}
+
+/// Follows the beam one step at a time, calling `step` with its heads at
+/// every step, until it only passes tiles in directions it already did.
+fn energized_tiles(
+    board: &[Vec<Tile>],
+    initial_beam: Beam,
+    mut step: impl FnMut(&[Beam]),
+) -> BitGrid {
+    let mut beams = vec![initial_beam];
+    let mut next = vec![];
+    let mut seen = Seen::new(board);
+    loop {
+        beams.retain(|beam| beam.pos.is_in_board(board) && seen.insert(*beam));
+        if beams.is_empty() {
+            return seen.energized();
+        }
+        step(&beams);
+        next.extend(beams.drain(..).flat_map(|beam| beam.step(board)).flatten());
+        std::mem::swap(&mut beams, &mut next);
+    }
+}
+
+/// The tiles passed by a beam in each direction.
+struct Seen([BitGrid; 4]);
+
+impl Seen {
+    fn new<T>(board: &[Vec<T>]) -> Self {
+        Self([(); 4].map(|_| BitGrid::from_board(board, |_| false)))
+    }
+
+    fn insert(&mut self, beam: Beam) -> bool {
+        self.0[beam.dir as usize].insert(beam.pos)
+    }
+
+    fn energized(&self) -> BitGrid {
+        let [up, left, down, right] = &self.0;
+        &(&(up | left) | down) | right
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Beam {
+    pos: Point,
+    dir: Direction,
+}
+
+impl Beam {
+    fn new(pos: Point, dir: Direction) -> Self {
+        Self { pos, dir }
+    }
+
+    /// The beams leaving the tile, one or two.
+    fn step(&self, board: &[Vec<Tile>]) -> [Option<Self>; 2] {
+        match self.pos.get(board).unwrap() {
+            Tile::Empty => [Some(self._simple_step()), None],
+            Tile::Mirror => {
+                let new_dir = match self.dir {
+                    Direction::Up => Direction::Right,
+                    Direction::Right => Direction::Up,
+                    Direction::Down => Direction::Left,
+                    Direction::Left => Direction::Down,
+                };
+                [Some(Beam::new(self.pos.step(new_dir), new_dir)), None]
+            }
+            Tile::BackMirror => {
+                let new_dir = match self.dir {
+                    Direction::Up => Direction::Left,
+                    Direction::Left => Direction::Up,
+                    Direction::Down => Direction::Right,
+                    Direction::Right => Direction::Down,
+                };
+                [Some(Beam::new(self.pos.step(new_dir), new_dir)), None]
+            }
+            Tile::VerticalSplitter => match self.dir {
+                Direction::Up | Direction::Down => [Some(self._simple_step()), None],
+                Direction::Left | Direction::Right => [
+                    Some(Beam::new(self.pos.up(), Direction::Up)),
+                    Some(Beam::new(self.pos.down(), Direction::Down)),
+                ],
+            },
+            Tile::HorizontalSplitter => match self.dir {
+                Direction::Left | Direction::Right => [Some(self._simple_step()), None],
+                Direction::Up | Direction::Down => [
+                    Some(Beam::new(self.pos.left(), Direction::Left)),
+                    Some(Beam::new(self.pos.right(), Direction::Right)),
+                ],
+            },
+        }
+    }
+
+    fn _simple_step(&self) -> Self {
+        Self::new(self.pos.step(self.dir), self.dir)
+    }
+}
+
+/// Draws the beam of part 1 spreading, one step per frame: the tiles it
+/// energized so far, and its heads in red. The last frame has no heads.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let board = parse(input)?;
+    let chars = crate::to_board(input);
+    let scene = |energized: &BitGrid| Scene::new(&chars).points(energized.iter(), Color::YELLOW);
+    let mut frames = Frames::new(canvas);
+    let mut energized = BitGrid::from_board(&board, |_| false);
+    let start = Beam::new(Point { x: 0, y: 0 }, Direction::Right);
+    energized_tiles(&board, start, |beams| {
+        energized.extend(beams.iter().map(|beam| beam.pos));
+        frames.draw(|| scene(&energized).points(beams.iter().map(|beam| beam.pos), Color::RED));
+    });
+    frames.draw(|| scene(&energized));
+    frames.result()
+}
+
+/// A `size` by `size` contraption.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| {
+                    if rng.chance(0.1) {
+                        *rng.choose(&['/', '\\', '|', '-'])
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(46));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(51));
+    }
+
+    #[test]
+    fn test_invalid_tile() {
+        let error = part1("./\n.x").unwrap_err();
+        assert_eq!(error.to_string(), "line 2 '.x': invalid tile 'x'");
+        assert_eq!(part2(""), Ok(0));
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut frames = vec![];
+        visualize(INPUT, &mut frames).unwrap();
+        let energized = frames
+            .last()
+            .unwrap()
+            .cells()
+            .concat()
+            .iter()
+            .filter(|c| c.bg.is_some())
+            .count();
+        assert_eq!(energized, 46);
+    }
+}
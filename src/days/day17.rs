@@ -0,0 +1,174 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::io;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::{Canvas, Color, Scene};
+use crate::{Direction, Point};
+
+pub fn part1(input: &str) -> error::Result<i32> {
+    let board = parse(input)?;
+    let start = Point::new(0, 0);
+    let end = Point::new(board[0].len() as i32 - 1, board.len() as i32 - 1);
+    Ok(shortest(&board, start, end, 1, 3).0)
+}
+
+pub fn part2(input: &str) -> error::Result<i32> {
+    let board = parse(input)?;
+    let start = Point::new(0, 0);
+    let end = Point::new(board[0].len() as i32 - 1, board.len() as i32 - 1);
+    Ok(shortest(&board, start, end, 4, 10).0)
+}
+
+/// The heat loss of every block of the map, which is not empty.
+pub fn parse(input: &str) -> error::Result<Vec<Vec<i32>>> {
+    let board = error::parse_grid(input, |c| {
+        c.to_digit(10)
+            .map(|n| n as i32)
+            .ok_or_else(|| Error::new(format!("invalid heat loss '{}'", c)))
+    })?;
+    if board.first().is_none_or(Vec::is_empty) {
+        return Err(Error::new("the map is empty"));
+    }
+    Ok(board)
+}
+
+/// Draws the heat loss of every block and the routes of parts 1 and 2, one per frame.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let board = parse(input)?;
+    let start = Point::new(0, 0);
+    let end = Point::new(board[0].len() as i32 - 1, board.len() as i32 - 1);
+    let heatmap = board.iter().enumerate().flat_map(|(y, row)| {
+        row.iter()
+            .enumerate()
+            .map(move |(x, n)| (Point::new(x as i32, y as i32), *n))
+    });
+    let scene = Scene::from_digits(&board).heatmap(heatmap);
+    for (min, max) in [(1, 3), (4, 10)] {
+        let (_, route) = shortest(&board, start, end, min, max);
+        canvas.draw(&scene.clone().path(route, Color::BLACK))?;
+    }
+    Ok(())
+}
+
+type State = (Point, Direction, i32);
+
+/// The least heat loss from `start` to `end`, and the route with that loss.
+fn shortest(
+    board: &[Vec<i32>],
+    start: Point,
+    end: Point,
+    min_consecutive_steps: i32,
+    max_consecutive_steps: i32,
+) -> (i32, Vec<Point>) {
+    let mut heap = BinaryHeap::new();
+    // The state each seen state was reached from.
+    let mut seen: HashMap<State, Option<State>> = HashMap::new();
+    heap.push((0, start, Direction::Right, 0, None));
+    heap.push((0, start, Direction::Down, 0, None));
+    while let Some((heat_loss, pos, direction, consecutive_steps_in_direction, from)) = heap.pop() {
+        // If reached goal - return
+        if pos == end {
+            let mut route = vec![pos];
+            let mut from: Option<State> = from;
+            while let Some(state) = from {
+                route.push(state.0);
+                from = seen[&state];
+            }
+            route.reverse();
+            return (-heat_loss, route);
+        }
+
+        // if already seen - skip
+        let state = (pos, direction, consecutive_steps_in_direction);
+        if seen.contains_key(&state) {
+            continue;
+        }
+        seen.insert(state, from);
+
+        if consecutive_steps_in_direction < max_consecutive_steps {
+            let np = pos.step(direction);
+            if let Some(n) = np.get(board) {
+                heap.push((
+                    heat_loss - n,
+                    np,
+                    direction,
+                    consecutive_steps_in_direction + 1,
+                    Some(state),
+                ));
+            }
+        }
+
+        if consecutive_steps_in_direction >= min_consecutive_steps {
+            let np = pos.step(direction.rotate_left());
+            if let Some(n) = np.get(board) {
+                heap.push((heat_loss - n, np, direction.rotate_left(), 1, Some(state)));
+            }
+            let np = pos.step(direction.rotate_right());
+            if let Some(n) = np.get(board) {
+                heap.push((heat_loss - n, np, direction.rotate_right(), 1, Some(state)));
+            }
+        }
+    }
+    panic!("No path from {:?} to {:?}", start, end);
+}
+
+/// A `size` by `size` heat map, at least 5 by 5 so that ultra crucibles can cross it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(5))
+        .map(|_| {
+            (0..size.max(5))
+                .map(|_| char::from_digit(rng.range(1..10) as u32, 10).unwrap())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(102));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(94));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("12\n3x").unwrap_err().to_string(),
+            "line 2 '3x': invalid heat loss 'x'"
+        );
+        assert_eq!(part1("").unwrap_err().message(), "the map is empty");
+    }
+
+    #[test]
+    fn test_route() {
+        let board = crate::to_num_board(INPUT);
+        let end = Point::new(12, 12);
+        let (heat_loss, route) = shortest(&board, Point::new(0, 0), end, 1, 3);
+        assert_eq!(route.first(), Some(&Point::new(0, 0)));
+        assert_eq!(route.last(), Some(&end));
+        let route_loss: i32 = route[1..].iter().map(|p| p.get(&board).unwrap()).sum();
+        assert_eq!(route_loss, heat_loss);
+    }
+}
@@ -0,0 +1,476 @@
+use crate::error::{self, Error};
+use crate::{Direction, Point};
+use itertools::Itertools;
+
+use crate::generate::{self, Rng};
+use crate::render::svg::Svg;
+use crate::render::Color;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let moves = parse(input)?
+        .iter()
+        .map(|step| (step.direction, step.length))
+        .collect_vec();
+    check_loop(input, &moves)?;
+    let points = get_points(&moves);
+    let area = get_area(&points)?;
+    Ok(points.len() + area)
+}
+pub fn part2(input: &str) -> error::Result<i64> {
+    let moves = parse(input)?.iter().map(Step::hidden_move).collect_vec();
+    check_loop(input, &moves)?;
+    let corners = get_corners(&moves);
+    // Cannot happen once the trench is a loop that never meets itself.
+    let unpaired = || Error::new("the corners of the trench do not pair up");
+    let mut active_xs = HashSet::new();
+    let mut result = 0;
+    let mut prev_y = corners[0].p.y;
+    for chunk in &corners.iter().chunks(2) {
+        let (c1, c2) = chunk.collect_tuple().ok_or_else(unpaired)?;
+        if c1.p.y != c2.p.y || c1.p.x >= c2.p.x {
+            return Err(unpaired());
+        }
+
+        if c1.p.y >= prev_y {
+            let height = (c1.p.y - prev_y + 1) as i64;
+            let width = active_xs.len() as i64;
+            result += height * width;
+            prev_y = c1.p.y + 1;
+        }
+
+        let was_inside = active_xs.contains(&(c1.p.x + 1));
+
+        // flip columns in the middle.
+        for x in c1.p.x + 1..c2.p.x {
+            if active_xs.insert(x) {
+                result += 1;
+            } else {
+                active_xs.remove(&x);
+            }
+        }
+
+        match c1.ct {
+            CornerType::DownRight => {
+                if active_xs.insert(c1.p.x) {
+                    result += 1;
+                }
+            }
+            CornerType::UpRight => {
+                if was_inside {
+                    active_xs.remove(&c1.p.x);
+                }
+            }
+            _ => return Err(unpaired()),
+        }
+
+        match c2.ct {
+            CornerType::DownLeft => {
+                if active_xs.insert(c2.p.x) {
+                    result += 1;
+                }
+            }
+            CornerType::UpLeft => {
+                if was_inside {
+                    active_xs.remove(&c2.p.x);
+                }
+            }
+            _ => return Err(unpaired()),
+        }
+    }
+    if !active_xs.is_empty() {
+        return Err(unpaired());
+    }
+
+    Ok(result)
+}
+
+pub fn parse(input: &str) -> error::Result<Vec<Step>> {
+    error::parse_lines(input)
+}
+
+/// Checks that the moves, one per line of the input, dig a trench around a
+/// single lagoon: every move turns, the last one ends where the first one
+/// starts, and the trench never meets itself elsewhere.
+fn check_loop(input: &str, moves: &[(Direction, usize)]) -> error::Result<()> {
+    let error = |i: usize, message: &str| {
+        let line = input.lines().nth(i).unwrap_or_default();
+        Error::new(message).at_line(i + 1, line)
+    };
+    if moves.is_empty() {
+        return Err(Error::new("the dig plan is empty"));
+    }
+    let mut p = Point::new(0, 0);
+    let mut trenches = vec![];
+    for (i, (direction, length)) in moves.iter().enumerate() {
+        let previous = moves[(i + moves.len() - 1) % moves.len()].0;
+        if *length == 0 {
+            return Err(error(i, "the step digs no trench"));
+        }
+        if previous == *direction || previous == direction.opposite() {
+            return Err(error(i, "the step does not turn left or right"));
+        }
+        let np = p.steps(*direction, *length as i32);
+        trenches.push((p.x.min(np.x), p.x.max(np.x), p.y.min(np.y), p.y.max(np.y)));
+        p = np;
+    }
+    if p != Point::new(0, 0) {
+        return Err(error(
+            moves.len() - 1,
+            "the trench does not end where it starts",
+        ));
+    }
+    // Trenches along the axes meet when their bounding boxes do.
+    for (j, b) in trenches.iter().enumerate() {
+        for (i, a) in trenches[..j].iter().enumerate() {
+            let adjacent = i + 1 == j || (i == 0 && j == trenches.len() - 1);
+            if !adjacent && a.0 <= b.1 && b.0 <= a.1 && a.2 <= b.3 && b.2 <= a.3 {
+                let message = format!("the trench meets the one of line {}", i + 1);
+                return Err(error(j, &message));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn get_points(moves: &[(Direction, usize)]) -> HashSet<Point> {
+    let mut p = Point::new(0, 0);
+    let mut points = HashSet::new();
+    points.insert(p);
+    for (direction, length) in moves {
+        for _ in 0..*length {
+            p = p.step(*direction);
+            points.insert(p);
+        }
+    }
+    points
+}
+
+fn get_area(points: &HashSet<Point>) -> error::Result<usize> {
+    // assume center is inside
+    let (xs, ys) = (points.iter().map(|p| p.x), points.iter().map(|p| p.y));
+    let (x_min, x_max) = xs.minmax().into_option().unwrap_or_default();
+    let (y_min, y_max) = ys.minmax().into_option().unwrap_or_default();
+    let center = Point::new((x_min + x_max) / 2, (y_min + y_max) / 2);
+    let outside = || Error::new("the middle of the dig plan is not inside the lagoon");
+    if points.contains(&center) {
+        return Err(outside());
+    }
+    let mut area_points = HashSet::new();
+    let mut queue = vec![center];
+    while let Some(p) = queue.pop() {
+        if points.contains(&p) {
+            continue;
+        }
+        if !(x_min..=x_max).contains(&p.x) || !(y_min..=y_max).contains(&p.y) {
+            return Err(outside());
+        }
+        if area_points.insert(p) {
+            for np in [p.up(), p.down(), p.left(), p.right()] {
+                queue.push(np);
+            }
+        }
+    }
+    Ok(area_points.len())
+}
+
+/// Draws the dig plan of part 1, every edge in its own colour.
+pub fn drawing(input: &str) -> error::Result<Svg> {
+    let mut p = Point::new(0, 0);
+    let mut edges = vec![];
+    for step in parse(input)? {
+        let np = p.steps(step.direction, step.length as i32);
+        let rgb = step.color;
+        edges.push((p, np, Color((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)));
+        p = np;
+    }
+    let xy = |p: Point| (p.x as f64, p.y as f64);
+    let corners = edges.iter().map(|(p, _, _)| xy(*p));
+    let mut svg = Svg::new().polygon(corners, Color::GREY, Color::GREY);
+    for (from, to, color) in edges {
+        svg = svg.polyline([xy(from), xy(to)], color);
+    }
+    Ok(svg)
+}
+
+/// A line of the dig plan, like `R 6 (#70c710)`.
+pub struct Step {
+    direction: Direction,
+    length: usize,
+    color: u32,
+}
+
+impl FromStr for Step {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir, len, rgb) = s
+            .split(' ')
+            .collect_tuple()
+            .ok_or_else(|| Error::new("expected a direction, a length and a colour"))?;
+        let color = rgb
+            .strip_prefix("(#")
+            .and_then(|rgb| rgb.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .filter(|color| color & 0xf < 4)
+            .ok_or_else(|| Error::new(format!("invalid colour '{}'", rgb)))?;
+        Ok(Step {
+            direction: dir.parse()?,
+            length: error::parse(len)?,
+            color,
+        })
+    }
+}
+
+impl Step {
+    /// The move of part 2, hidden in the colour: five hex digits of length
+    /// and one of direction.
+    fn hidden_move(&self) -> (Direction, usize) {
+        let direction = match self.color & 0xf {
+            0 => Direction::Right,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Up,
+        };
+        (direction, (self.color >> 4) as usize)
+    }
+}
+
+fn get_corners(moves: &[(Direction, usize)]) -> Vec<Corner> {
+    let mut p = Point::new(0, 0);
+    let mut prev_direction = moves.last().unwrap().0.opposite();
+    let mut corners = vec![];
+    for (direction, length) in moves {
+        corners.push(Corner::new(p, CornerType::from(prev_direction, *direction)));
+        p = p.steps(*direction, *length as i32);
+        prev_direction = direction.opposite();
+    }
+    corners.sort_by_key(|c| (c.p.y, c.p.x, c.ct));
+    corners
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+enum CornerType {
+    DownLeft,
+    DownRight,
+    UpLeft,
+    UpRight,
+}
+
+impl CornerType {
+    fn from(d1: Direction, d2: Direction) -> Self {
+        match (d1, d2) {
+            (Direction::Down, Direction::Left) | (Direction::Left, Direction::Down) => {
+                Self::DownLeft
+            }
+            (Direction::Down, Direction::Right) | (Direction::Right, Direction::Down) => {
+                Self::DownRight
+            }
+            (Direction::Up, Direction::Left) | (Direction::Left, Direction::Up) => Self::UpLeft,
+            (Direction::Up, Direction::Right) | (Direction::Right, Direction::Up) => Self::UpRight,
+            _ => panic!("Invalid corner {:?} {:?}", d1, d2),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+struct Corner {
+    p: Point,
+    ct: CornerType,
+}
+
+impl Corner {
+    fn new(p: Point, ct: CornerType) -> Self {
+        Self { p, ct }
+    }
+}
+
+/// A dig plan around a random region of a `size` by `size` grid, with edges of
+/// up to 10 metres in the plan and much longer ones in the colour codes.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2) as i64;
+    let max_gap = 50000.min(0xfffff / size);
+    loop {
+        let cells = (size * size / 2) as usize;
+        let region = generate::grow_region(rng, size, size, (size / 2, size / 2), cells);
+        let corners = generate::outline(&region);
+        let (Some(short), Some(long)) = (
+            dig(rng, &region, &corners, 10),
+            dig(rng, &region, &corners, max_gap),
+        ) else {
+            continue;
+        };
+        return short
+            .iter()
+            .zip(long)
+            .map(|((direction, length), (_, color_length))| {
+                let (name, digit) = match direction {
+                    Direction::Right => ('R', 0),
+                    Direction::Down => ('D', 1),
+                    Direction::Left => ('L', 2),
+                    Direction::Up => ('U', 3),
+                };
+                format!("{} {} (#{:05x}{})", name, length, color_length, digit)
+            })
+            .join("\n");
+    }
+}
+
+/// The moves along the outline of a region, with random gaps of up to
+/// `max_gap` between grid lines. `None` if the middle of the dug out area is
+/// not inside it, as `part1` assumes.
+fn dig(
+    rng: &mut Rng,
+    region: &BTreeSet<(i64, i64)>,
+    corners: &[(i64, i64)],
+    max_gap: i64,
+) -> Option<Vec<(Direction, i64)>> {
+    let mut grid_lines = |count: i64| {
+        (0..=count)
+            .scan(0, |at, _| {
+                // Wider than 1, so that the trench never touches itself.
+                *at += rng.range(2..max_gap + 1);
+                Some(*at)
+            })
+            .collect_vec()
+    };
+    let xs = grid_lines(corners.iter().map(|c| c.0).max().unwrap());
+    let ys = grid_lines(corners.iter().map(|c| c.1).max().unwrap());
+
+    // Same coordinates as the solver, which starts digging at the first corner.
+    let (x0, y0) = (xs[corners[0].0 as usize], ys[corners[0].1 as usize]);
+    let xs = xs.iter().map(|x| x - x0).collect_vec();
+    let ys = ys.iter().map(|y| y - y0).collect_vec();
+    let (min_x, max_x) = corners
+        .iter()
+        .map(|c| xs[c.0 as usize])
+        .minmax()
+        .into_option()?;
+    let (min_y, max_y) = corners
+        .iter()
+        .map(|c| ys[c.1 as usize])
+        .minmax()
+        .into_option()?;
+    let (mid_x, mid_y) = ((min_x + max_x) / 2, (min_y + max_y) / 2);
+    let cell_x = xs.windows(2).position(|w| w[0] < mid_x && mid_x < w[1])?;
+    let cell_y = ys.windows(2).position(|w| w[0] < mid_y && mid_y < w[1])?;
+    if !region.contains(&(cell_x as i64, cell_y as i64)) {
+        return None;
+    }
+
+    Some(
+        corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|((x1, y1), (x2, y2))| {
+                let dx = xs[*x2 as usize] - xs[*x1 as usize];
+                let dy = ys[*y2 as usize] - ys[*y1 as usize];
+                let direction = match (dx.signum(), dy.signum()) {
+                    (1, 0) => Direction::Right,
+                    (-1, 0) => Direction::Left,
+                    (0, 1) => Direction::Down,
+                    _ => Direction::Up,
+                };
+                (direction, dx.abs() + dy.abs())
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(62));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(952408144115));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = part1("R 6 (#70c710)\nX 5 (#0dc571)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 'X 5 (#0dc571)': unknown direction 'X'"
+        );
+        assert_eq!(part2("R 6 (#70c714)").unwrap_err().line(), Some(1));
+    }
+
+    #[test]
+    fn test_invalid_plans() {
+        let message = |input| part1(input).unwrap_err().to_string();
+        assert_eq!(message(""), "the dig plan is empty");
+        assert_eq!(
+            message("R 2 (#000000)\nD 0 (#000000)\nL 2 (#000000)\nU 0 (#000000)"),
+            "line 2 'D 0 (#000000)': the step digs no trench"
+        );
+        assert_eq!(
+            message("R 2 (#000000)\nR 2 (#000000)\nD 2 (#000000)\nL 4 (#000000)\nU 2 (#000000)"),
+            "line 2 'R 2 (#000000)': the step does not turn left or right"
+        );
+        assert_eq!(
+            message("R 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 1 (#000000)"),
+            "line 4 'U 1 (#000000)': the trench does not end where it starts"
+        );
+        let crossing = "R 4 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 4 (#000000)\n\
+                        L 2 (#000000)\nD 2 (#000000)";
+        assert_eq!(
+            message(crossing),
+            "line 4 'U 4 (#000000)': the trench meets the one of line 1"
+        );
+        assert_eq!(part2(crossing).unwrap_err().line(), Some(1));
+        // Two lagoons that share a corner between them.
+        let hourglass = "R 2 (#000000)\nD 2 (#000000)\nR 2 (#000000)\nD 2 (#000000)\n\
+                         L 2 (#000000)\nU 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)";
+        assert_eq!(
+            message(hourglass),
+            "line 6 'U 2 (#000000)': the trench meets the one of line 2"
+        );
+        assert_eq!(
+            message(
+                "R 4 (#000000)\nD 4 (#000000)\nL 2 (#000000)\nU 2 (#000000)\n\
+                     R 1 (#000000)\nU 1 (#000000)\nL 3 (#000000)\nU 1 (#000000)"
+            ),
+            "the middle of the dig plan is not inside the lagoon"
+        );
+        assert_eq!(
+            message(
+                "R 6 (#000000)\nD 6 (#000000)\nL 2 (#000000)\nU 5 (#000000)\n\
+                     L 2 (#000000)\nD 5 (#000000)\nL 2 (#000000)\nU 6 (#000000)"
+            ),
+            "the middle of the dig plan is not inside the lagoon"
+        );
+    }
+
+    #[test]
+    fn test_drawing() {
+        let mut out = vec![];
+        drawing(INPUT).unwrap().write(&mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.contains("<polygon points=\"0,0 6,0 6,5 4,5 "));
+        assert!(svg.contains("<polyline points=\"0,0 6,0\" fill=\"none\" stroke=\"#70c710\""));
+    }
+}
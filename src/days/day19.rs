@@ -0,0 +1,609 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::interner::Interner;
+use crate::render::dot::{Dot, Edge, Node, Shape};
+use crate::render::Color;
+use itertools::{iproduct, Itertools};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp::{max, min};
+use std::str::FromStr;
+
+lazy_static! {
+    static ref PART_RE: Regex = Regex::new(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}").unwrap();
+}
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let System { workflows, parts } = parse(input)?;
+
+    Ok(parts
+        .iter()
+        .filter(|p| p.is_accepted_by(&workflows))
+        .map(Part::value)
+        .sum())
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let workflows = parse(input)?.workflows;
+
+    count_accepted(
+        &workflows,
+        START,
+        InclusiveRange::new(),
+        InclusiveRange::new(),
+        InclusiveRange::new(),
+        InclusiveRange::new(),
+    )
+}
+
+/// The workflows that sort the parts, and the parts to sort.
+pub struct System<'a> {
+    workflows: Workflows<'a>,
+    parts: Vec<Part>,
+}
+
+pub fn parse(input: &str) -> error::Result<System<'_>> {
+    let (workflows_str, parts_str) = split_input(input)?;
+    let workflows = Workflows::try_from(workflows_str)?;
+    // The parts come after the workflows and a blank line.
+    let first = workflows_str.lines().count() + 2;
+    let parts = error::parse_lines_from::<Part>(parts_str, first)?;
+    Ok(System { workflows, parts })
+}
+
+fn split_input(input: &str) -> error::Result<(&str, &str)> {
+    input
+        .split_once("\n\n")
+        .ok_or_else(|| Error::new("expected a blank line between the workflows and the parts"))
+}
+
+/// The workflows, with an edge per rule labelled by its condition. Parts start
+/// at `in` and end up accepted or rejected.
+pub fn graph(input: &str) -> error::Result<Dot> {
+    let (workflows_str, _) = split_input(input)?;
+    let workflows = Workflows::try_from(workflows_str)?;
+    let name = |id: usize| workflows.names.name(id);
+    let mut dot = Dot::directed();
+    for (name, fill) in [
+        ("in", Color::YELLOW),
+        ("A", Color::GREEN),
+        ("R", Color::RED),
+    ] {
+        let shape = if name == "in" {
+            Shape::House
+        } else {
+            Shape::DoubleCircle
+        };
+        let fill = Some(fill);
+        dot.node(
+            name,
+            Node {
+                shape,
+                fill,
+                ..Node::default()
+            },
+        );
+    }
+    for wf in workflows.iter().filter(|wf| wf.id != START) {
+        let shape = Shape::Box;
+        dot.node(
+            name(wf.id),
+            Node {
+                shape,
+                ..Node::default()
+            },
+        );
+    }
+    for wf in workflows.iter() {
+        for rule in &wf.rules {
+            let label = rule.condition();
+            dot.edge(
+                name(wf.id),
+                name(rule.target()),
+                Edge {
+                    label,
+                    ..Edge::default()
+                },
+            );
+        }
+    }
+    Ok(dot)
+}
+
+/// Checks one part per box between the rule constants, as a reference for
+/// `part2`. Rules only compare with constants, so all the parts in a box take
+/// the same route.
+pub fn part2_reference(input: &str) -> error::Result<usize> {
+    let (workflows_str, _) = split_input(input)?;
+    let workflows = Workflows::try_from(workflows_str)?;
+
+    let mut splits = [(); 4].map(|_| vec![1, 4001]);
+    for rule in workflows.iter().flat_map(|wf| &wf.rules) {
+        if let Some((category, split)) = rule.split() {
+            splits[category].push(split);
+        }
+    }
+    let [xs, ms, as_, ss] = splits.map(|mut splits| {
+        splits.sort();
+        splits.dedup();
+        splits.windows(2).map(|w| (w[0], w[1] - w[0])).collect_vec()
+    });
+    Ok(iproduct!(xs, ms, as_, ss)
+        .filter(|((x, _), (m, _), (a, _), (s, _))| {
+            let part = Part {
+                x: *x,
+                m: *m,
+                a: *a,
+                s: *s,
+            };
+            part.is_accepted_by(&workflows)
+        })
+        .map(|((_, x), (_, m), (_, a), (_, s))| x * m * a * s)
+        .sum())
+}
+
+fn count_accepted(
+    workflows: &Workflows,
+    curr: usize,
+    mut x: InclusiveRange,
+    mut m: InclusiveRange,
+    mut a: InclusiveRange,
+    mut s: InclusiveRange,
+) -> error::Result<usize> {
+    if let Some(workflow) = workflows.get(curr) {
+        let mut result = 0;
+        for rule in &workflow.rules {
+            let (nx, x1) = rule.cut_x(x);
+            x = x1;
+            let (nm, m1) = rule.cut_m(m);
+            m = m1;
+            let (na, a1) = rule.cut_a(a);
+            a = a1;
+            let (ns, s1) = rule.cut_s(s);
+            s = s1;
+
+            if !nx.is_empty() && !nm.is_empty() && !na.is_empty() && !ns.is_empty() {
+                result += count_accepted(workflows, rule.target(), nx, nm, na, ns)?;
+            }
+        }
+        Ok(result)
+    } else if curr == REJECTED {
+        Ok(0)
+    } else if curr == ACCEPTED {
+        Ok(x.len() * m.len() * a.len() * s.len())
+    } else {
+        let message = format!("unknown workflow '{}'", workflows.names.name(curr));
+        Err(Error::new(message))
+    }
+}
+
+enum Rule {
+    XGreater(usize, usize),
+    XLess(usize, usize),
+    MGreater(usize, usize),
+    MLess(usize, usize),
+    AGreater(usize, usize),
+    ALess(usize, usize),
+    SGreater(usize, usize),
+    SLess(usize, usize),
+    Default(usize),
+}
+
+impl Rule {
+    fn parse<'a>(value: &'a str, names: &mut Interner<'a>) -> error::Result<Self> {
+        let Some((rule, target)) = value.split_once(':') else {
+            return Ok(Rule::Default(names.intern(value)));
+        };
+        let target = names.intern(target);
+        let constant = error::parse(rule.get(2..).unwrap_or_default())?;
+        Ok(match rule.get(..2) {
+            Some("x>") => Rule::XGreater(constant, target),
+            Some("x<") => Rule::XLess(constant, target),
+            Some("m>") => Rule::MGreater(constant, target),
+            Some("m<") => Rule::MLess(constant, target),
+            Some("a>") => Rule::AGreater(constant, target),
+            Some("a<") => Rule::ALess(constant, target),
+            Some("s>") => Rule::SGreater(constant, target),
+            Some("s<") => Rule::SLess(constant, target),
+            _ => return Err(Error::new(format!("invalid rule '{}'", value))),
+        })
+    }
+
+    fn process(&self, p: &Part) -> Option<usize> {
+        match self {
+            Rule::XGreater(n, s) if p.x > *n => Some(*s),
+            Rule::XLess(n, s) if p.x < *n => Some(*s),
+            Rule::MGreater(n, s) if p.m > *n => Some(*s),
+            Rule::MLess(n, s) if p.m < *n => Some(*s),
+            Rule::AGreater(n, s) if p.a > *n => Some(*s),
+            Rule::ALess(n, s) if p.a < *n => Some(*s),
+            Rule::SGreater(n, s) if p.s > *n => Some(*s),
+            Rule::SLess(n, s) if p.s < *n => Some(*s),
+            Rule::Default(s) => Some(*s),
+            _ => None,
+        }
+    }
+
+    fn target(&self) -> usize {
+        match self {
+            Rule::XGreater(_, s) => *s,
+            Rule::XLess(_, s) => *s,
+            Rule::MGreater(_, s) => *s,
+            Rule::MLess(_, s) => *s,
+            Rule::AGreater(_, s) => *s,
+            Rule::ALess(_, s) => *s,
+            Rule::SGreater(_, s) => *s,
+            Rule::SLess(_, s) => *s,
+            Rule::Default(s) => *s,
+        }
+    }
+
+    fn condition(&self) -> Option<String> {
+        let (category, op, n) = match self {
+            Rule::XGreater(n, _) => ('x', '>', n),
+            Rule::XLess(n, _) => ('x', '<', n),
+            Rule::MGreater(n, _) => ('m', '>', n),
+            Rule::MLess(n, _) => ('m', '<', n),
+            Rule::AGreater(n, _) => ('a', '>', n),
+            Rule::ALess(n, _) => ('a', '<', n),
+            Rule::SGreater(n, _) => ('s', '>', n),
+            Rule::SLess(n, _) => ('s', '<', n),
+            Rule::Default(_) => return None,
+        };
+        Some(format!("{}{}{}", category, op, n))
+    }
+
+    /// The category the rule compares (in "xmas" order), and the smallest
+    /// value above the cut it makes.
+    fn split(&self) -> Option<(usize, usize)> {
+        match self {
+            Rule::XGreater(n, _) => Some((0, n + 1)),
+            Rule::XLess(n, _) => Some((0, *n)),
+            Rule::MGreater(n, _) => Some((1, n + 1)),
+            Rule::MLess(n, _) => Some((1, *n)),
+            Rule::AGreater(n, _) => Some((2, n + 1)),
+            Rule::ALess(n, _) => Some((2, *n)),
+            Rule::SGreater(n, _) => Some((3, n + 1)),
+            Rule::SLess(n, _) => Some((3, *n)),
+            Rule::Default(_) => None,
+        }
+    }
+
+    fn cut_x(&self, x: InclusiveRange) -> (InclusiveRange, InclusiveRange) {
+        match self {
+            Rule::XGreater(n, _) => (x.greater(*n), x.less_equal(*n)),
+            Rule::XLess(n, _) => (x.less(*n), x.greater_equal(*n)),
+            _ => (x, x),
+        }
+    }
+
+    fn cut_m(&self, m: InclusiveRange) -> (InclusiveRange, InclusiveRange) {
+        match self {
+            Rule::MGreater(n, _) => (m.greater(*n), m.less_equal(*n)),
+            Rule::MLess(n, _) => (m.less(*n), m.greater_equal(*n)),
+            _ => (m, m),
+        }
+    }
+
+    fn cut_a(&self, a: InclusiveRange) -> (InclusiveRange, InclusiveRange) {
+        match self {
+            Rule::AGreater(n, _) => (a.greater(*n), a.less_equal(*n)),
+            Rule::ALess(n, _) => (a.less(*n), a.greater_equal(*n)),
+            _ => (a, a),
+        }
+    }
+
+    fn cut_s(&self, s: InclusiveRange) -> (InclusiveRange, InclusiveRange) {
+        match self {
+            Rule::SGreater(n, _) => (s.greater(*n), s.less_equal(*n)),
+            Rule::SLess(n, _) => (s.less(*n), s.greater_equal(*n)),
+            _ => (s, s),
+        }
+    }
+}
+
+// The ids of the start and of the final states, which are interned first.
+const START: usize = 0;
+const ACCEPTED: usize = 1;
+const REJECTED: usize = 2;
+
+/// The workflows by the ids of their names. The final states have ids, but no
+/// workflows.
+struct Workflows<'a> {
+    names: Interner<'a>,
+    workflows: Vec<Option<Workflow>>,
+}
+
+impl<'a> TryFrom<&'a str> for Workflows<'a> {
+    type Error = Error;
+
+    fn try_from(value: &'a str) -> error::Result<Self> {
+        let mut names = Interner::new();
+        for name in ["in", "A", "R"] {
+            names.intern(name);
+        }
+        let parsed = value
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                Workflow::parse(line, &mut names).map_err(|error| error.at_line(i + 1, line))
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+        // Every rule must lead to a workflow or to a final state.
+        let mut defined = vec![false; names.len()];
+        parsed.iter().for_each(|wf| defined[wf.id] = true);
+        if !defined[START] {
+            return Err(Error::new("no workflow 'in'"));
+        }
+        for (i, (line, wf)) in value.lines().zip(&parsed).enumerate() {
+            let mut targets = wf.rules.iter().map(Rule::target);
+            if let Some(target) = targets.find(|t| *t > REJECTED && !defined[*t]) {
+                let message = format!("unknown workflow '{}'", names.name(target));
+                return Err(Error::new(message).at_line(i + 1, line));
+            }
+        }
+        let ids = parsed.iter().map(|wf| wf.id).collect_vec();
+        let mut workflows = (0..names.len()).map(|_| None).collect_vec();
+        for wf in parsed {
+            let id = wf.id;
+            workflows[id] = Some(wf);
+        }
+        let workflows = Self { names, workflows };
+        let size = workflows.names.len();
+        let (mut on_path, mut done) = (vec![false; size], vec![false; size]);
+        if let Some(id) = workflows.find_loop(START, &mut on_path, &mut done) {
+            let name = workflows.names.name(id);
+            let i = ids.iter().rposition(|wf| *wf == id).unwrap_or_default();
+            let line = value.lines().nth(i).unwrap_or_default();
+            let message = format!("the rules of workflow '{}' lead back to it", name);
+            return Err(Error::new(message).at_line(i + 1, line));
+        }
+        Ok(workflows)
+    }
+}
+
+impl<'a> Workflows<'a> {
+    fn get(&self, id: usize) -> Option<&Workflow> {
+        self.workflows.get(id)?.as_ref()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Workflow> {
+        self.workflows.iter().flatten()
+    }
+
+    /// Finds a workflow reachable from `id` with a rule back to one on the
+    /// path that led to it, as parts would go round forever.
+    fn find_loop(&self, id: usize, on_path: &mut [bool], done: &mut [bool]) -> Option<usize> {
+        let workflow = self.get(id).filter(|_| !done[id])?;
+        on_path[id] = true;
+        for target in workflow.rules.iter().map(Rule::target) {
+            if on_path[target] {
+                return Some(id);
+            }
+            if let Some(found) = self.find_loop(target, on_path, done) {
+                return Some(found);
+            }
+        }
+        on_path[id] = false;
+        done[id] = true;
+        None
+    }
+}
+
+struct Workflow {
+    id: usize,
+    rules: Vec<Rule>,
+}
+
+impl Workflow {
+    fn parse<'a>(value: &'a str, names: &mut Interner<'a>) -> error::Result<Self> {
+        let (name, args) = value
+            .split_once('{')
+            .ok_or_else(|| Error::new("expected a workflow like 'px{a<2006:qkq,rfg}'"))?;
+        let id = names.intern(name);
+        let rules = args
+            .trim_end_matches('}')
+            .split(',')
+            .map(|rule| Rule::parse(rule, names))
+            .collect::<error::Result<Vec<_>>>()?;
+        if !matches!(rules.last(), Some(Rule::Default(_))) {
+            return Err(Error::new("the last rule must have no condition"));
+        }
+        Ok(Self { id, rules })
+    }
+
+    fn process(&self, p: &Part) -> usize {
+        self.rules
+            .iter()
+            .filter_map(|r| r.process(p))
+            .next()
+            .unwrap()
+    }
+}
+
+struct Part {
+    x: usize,
+    m: usize,
+    a: usize,
+    s: usize,
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(value: &str) -> error::Result<Self> {
+        let (_, [x, m, a, s]) = PART_RE
+            .captures(value)
+            .ok_or_else(|| Error::new("expected a part like '{x=787,m=2655,a=1222,s=2876}'"))?
+            .extract();
+        Ok(Part {
+            x: error::parse(x)?,
+            m: error::parse(m)?,
+            a: error::parse(a)?,
+            s: error::parse(s)?,
+        })
+    }
+}
+
+impl Part {
+    fn is_accepted_by(&self, workflows: &Workflows) -> bool {
+        let mut curr = START;
+        while let Some(workflow) = workflows.get(curr) {
+            curr = workflow.process(self);
+        }
+        match curr {
+            REJECTED => false,
+            ACCEPTED => true,
+            _ => panic!("Part.is_accepted_by() failed"),
+        }
+    }
+
+    fn value(&self) -> usize {
+        self.x + self.m + self.a + self.s
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InclusiveRange(usize, usize);
+
+impl InclusiveRange {
+    fn new() -> Self {
+        Self(1, 4000)
+    }
+    fn is_empty(&self) -> bool {
+        self.1 < self.0
+    }
+    fn len(&self) -> usize {
+        self.1 - self.0 + 1
+    }
+    fn greater(&self, n: usize) -> Self {
+        Self(max(self.0, n + 1), self.1)
+    }
+    fn less(&self, n: usize) -> Self {
+        Self(self.0, min(self.1, n - 1))
+    }
+    fn greater_equal(&self, n: usize) -> Self {
+        Self(max(self.0, n), self.1)
+    }
+    fn less_equal(&self, n: usize) -> Self {
+        Self(self.0, min(self.1, n))
+    }
+}
+
+/// Up to `size` workflows, which like in real inputs form a tree under "in",
+/// and `size` parts, some of which are accepted.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = size.max(1);
+    loop {
+        let mut names = vec!["in".to_string()];
+        names.extend(rng.words(count - 1, 3, &["in"]));
+        let mut created = 1;
+        let mut workflows = vec![];
+        let mut i = 0;
+        while i < created {
+            let mut target = |rng: &mut Rng| {
+                if created < count && rng.chance(0.5) {
+                    created += 1;
+                    names[created - 1].clone()
+                } else {
+                    rng.choose(&["A", "R"]).to_string()
+                }
+            };
+            let mut rules = (0..1 + rng.below(3))
+                .map(|_| {
+                    let category = rng.choose(&['x', 'm', 'a', 's']);
+                    let op = rng.choose(&['<', '>']);
+                    let value = rng.range(1..4001);
+                    format!("{}{}{}:{}", category, op, value, target(rng))
+                })
+                .collect_vec();
+            rules.push(target(rng));
+            workflows.push(format!("{}{{{}}}", names[i], rules.join(",")));
+            i += 1;
+        }
+        rng.shuffle(&mut workflows);
+
+        let parts = (0..count)
+            .map(|_| {
+                let [x, m, a, s] = [(); 4].map(|_| rng.range(1..4001));
+                format!("{{x={},m={},a={},s={}}}", x, m, a, s)
+            })
+            .collect_vec();
+        let input = format!("{}\n\n{}", workflows.join("\n"), parts.join("\n"));
+        if part1(&input).is_ok_and(|sum| sum > 0) {
+            return input;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential;
+
+    const INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn test_graph() {
+        let dot = graph(INPUT).unwrap().to_string();
+        assert!(dot.contains("  \"in\" [shape=house, fillcolor=\"#e6be28\"]\n"));
+        assert!(dot.contains("  \"px\" [shape=box]\n"));
+        assert!(dot.contains("  \"px\" -> \"qkq\" [label=\"a<2006\"]\n"));
+        assert!(dot.contains("  \"px\" -> \"rfg\"\n"));
+        // One edge per rule.
+        assert_eq!(dot.matches(" -> ").count(), 25);
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(19114));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(167409079868000));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = part2("in{x<10:A,ab}\nab{y>2:R,A}\n\n{x=1,m=2,a=3,s=4}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 'ab{y>2:R,A}': invalid rule 'y>2:R'"
+        );
+        let error = part2("in{x<10:A,ab}\n\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1 'in{x<10:A,ab}': unknown workflow 'ab'"
+        );
+        let error = part1("in{A}\n\n{x=1,m=2,a=3,s=4}\n{x=1}").unwrap_err();
+        assert_eq!(error.line(), Some(4));
+        let error = part1("in{x<10:ab,R}\nab{m>5:A,cd}\ncd{in}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "line 3 'cd{in}': the rules of workflow 'cd' lead back to it"
+        );
+    }
+
+    #[test]
+    fn test_part2_matches_reference() {
+        differential::compare(19, 0..100, 1..=8, part2, part2_reference)
+            .unwrap_or_else(|mismatch| panic!("{}", mismatch));
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+pub fn part1(input: &str) -> error::Result<u32> {
+    let bag = "12 red, 13 green, 14 blue".parse()?;
+    Ok(possible_games(&parse(input)?, &bag).sum())
+}
+
+pub fn part2(input: &str) -> error::Result<u32> {
+    Ok(parse(input)?
+        .iter()
+        .map(|game| game.minimal_bag().power(&["red", "green", "blue"]))
+        .sum())
+}
+
+/// The games, one per line.
+pub fn parse(input: &str) -> error::Result<Vec<Game>> {
+    error::parse_lines(input)
+}
+
+/// The ids of the games that are possible with the cubes in the bag.
+pub fn possible_games<'a>(games: &'a [Game], bag: &'a Bag) -> impl Iterator<Item = u32> + 'a {
+    games
+        .iter()
+        .filter(|game| game.is_possible_with(bag))
+        .map(|game| game.id)
+}
+
+pub struct Game {
+    pub id: u32,
+    pub sets: Vec<Bag>,
+}
+
+lazy_static! {
+    static ref GAME_RE: Regex = Regex::new(r"Game (\d+):(.*)").unwrap();
+}
+
+impl FromStr for Game {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (_, [id, sets]) = GAME_RE
+            .captures(value)
+            .ok_or_else(|| Error::new("expected a game like 'Game 1: 3 blue, 4 red; 2 green'"))?
+            .extract();
+        let id = error::parse(id)?;
+        let sets = sets
+            .split(';')
+            .map(Bag::from_str)
+            .collect::<error::Result<Vec<_>>>()?;
+        Ok(Self { id, sets })
+    }
+}
+
+impl Game {
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.sets.iter().all(|set| bag.contains(set))
+    }
+
+    /// The smallest bag that every set could have been drawn from.
+    pub fn minimal_bag(&self) -> Bag {
+        self.sets
+            .iter()
+            .fold(Bag::default(), |bag, set| bag.max(set))
+    }
+}
+
+/// Cubes by colour, like a set drawn in a game or the content of a bag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bag(BTreeMap<String, u32>);
+
+lazy_static! {
+    static ref CUBES_RE: Regex = Regex::new(r"^(\d+) (\w+)$").unwrap();
+}
+
+/// Parses counted colours like `3 blue, 4 red`. Colours may not repeat.
+impl FromStr for Bag {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut bag = Bag::default();
+        for cubes in value
+            .split(',')
+            .map(str::trim)
+            .filter(|cubes| !cubes.is_empty())
+        {
+            let (_, [count, colour]) = CUBES_RE
+                .captures(cubes)
+                .ok_or_else(|| Error::new(format!("expected a count and a colour in '{}'", cubes)))?
+                .extract();
+            if bag
+                .0
+                .insert(colour.to_string(), error::parse(count)?)
+                .is_some()
+            {
+                return Err(Error::new(format!("colour '{}' given twice", colour)));
+            }
+        }
+        Ok(bag)
+    }
+}
+
+impl Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes = self
+            .0
+            .iter()
+            .map(|(colour, count)| format!("{} {}", count, colour))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+impl Bag {
+    /// The number of cubes of the colour, 0 for colours that are not in the bag.
+    pub fn count(&self, colour: &str) -> u32 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    /// Whether the other cubes could all have been drawn from this bag at once.
+    pub fn contains(&self, other: &Bag) -> bool {
+        other
+            .0
+            .iter()
+            .all(|(colour, count)| *count <= self.count(colour))
+    }
+
+    /// The largest count of every colour in either bag.
+    pub fn max(&self, other: &Bag) -> Bag {
+        let mut bag = self.clone();
+        for (colour, count) in &other.0 {
+            let max = bag.0.entry(colour.clone()).or_default();
+            *max = (*max).max(*count);
+        }
+        bag
+    }
+
+    /// The product of the counts of the colours.
+    pub fn power(&self, colours: &[&str]) -> u32 {
+        colours.iter().map(|colour| self.count(colour)).product()
+    }
+}
+
+/// `size` games of up to 6 sets each. Odd games draw no more cubes than the
+/// bag of part 1 holds, so some are possible.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (1..=size)
+        .map(|id| {
+            let most = if id % 2 == 1 { 12 } else { 20 };
+            let sets = (0..1 + rng.below(6))
+                .map(|_| {
+                    let mut colors = vec!["red", "green", "blue"];
+                    rng.shuffle(&mut colors);
+                    colors.truncate(1 + rng.below(3));
+                    colors
+                        .iter()
+                        .map(|color| format!("{} {}", rng.range(1..most + 1), color))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect::<Vec<_>>();
+            format!("Game {}: {}", id, sets.join("; "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(8));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(2286));
+    }
+
+    #[test]
+    fn test_any_colours() {
+        let games = parse("Game 1: 2 purple, 1 red\nGame 2: 4 purple\nGame 3: 1 teal").unwrap();
+        let bag = "20 red, 3 purple".parse().unwrap();
+        assert_eq!(possible_games(&games, &bag).collect::<Vec<_>>(), [1]);
+        assert_eq!(games[0].minimal_bag().to_string(), "2 purple, 1 red");
+        assert_eq!(games[0].minimal_bag().power(&["red", "green"]), 0);
+    }
+
+    #[test]
+    fn test_minimal_bag() {
+        let game: Game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green"
+            .parse()
+            .unwrap();
+        let bag = game.minimal_bag();
+        assert_eq!(bag, "4 red, 2 green, 6 blue".parse().unwrap());
+        assert!(game.is_possible_with(&bag));
+        assert!(!game.is_possible_with(&"4 red, 2 green, 5 blue".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_bag() {
+        assert!("3 red, 2 red".parse::<Bag>().is_err());
+        assert!("red 3".parse::<Bag>().is_err());
+        let error = part1("Game 1: 3 blue\nGame 2: 3 blue 4 red").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::interner::Interner;
+use crate::render::dot::{Dot, Edge, Node, Shape};
+use crate::render::Color;
+use num::integer::lcm;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let mut network = parse(input)?;
+    Ok((0..1000)
+        .map(|_| process_signal(&mut network))
+        .reduce(|(l1, h1), (l2, h2)| (l1 + l2, h1 + h2))
+        .map(|(l, h)| l * h)
+        .unwrap())
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let network = parse(input)?;
+    let rx = network
+        .names
+        .get("rx")
+        .ok_or_else(|| Error::new("there is no module 'rx'"))?;
+    let mut goals = vec![Goal::new(rx, Signal::Low)];
+    // find the conjunction inputs leading to the goal
+    for _ in 0..2 {
+        goals = goals
+            .drain(..)
+            .flat_map(|g| g.expand_conjunction_inputs(&network.modules))
+            .collect();
+    }
+    if goals.is_empty() {
+        return Err(Error::new("no module sends pulses to 'rx'"));
+    }
+    crate::debug!(
+        "goals: {:?}",
+        goals
+            .iter()
+            .map(|g| (network.names.name(g.module), g.signal))
+            .collect::<Vec<_>>()
+    );
+
+    // find period for each
+    let mut periods = goals
+        .iter()
+        .map(|g| g.find_period(network.clone()))
+        .collect::<error::Result<Vec<_>>>()?;
+    crate::debug!("periods: {:?}", periods);
+
+    // return LCM of all periods
+    Ok(periods.drain(..).reduce(lcm).unwrap())
+}
+
+/// The module network. Flip-flops are boxes, conjunctions diamonds, and
+/// modules without outputs (like `rx`) red.
+pub fn graph(input: &str) -> error::Result<Dot> {
+    let network = parse(input)?;
+    let name = |id: usize| network.names.name(id);
+    let mut ids = (0..network.modules.len()).collect::<Vec<_>>();
+    ids.sort_by_key(|id| name(*id));
+    let mut dot = Dot::directed();
+    for id in &ids {
+        let module = &network.modules[*id];
+        let node = match module.t {
+            ModuleType::Flipflop => Node {
+                shape: Shape::Box,
+                label: Some(format!("%{}", name(*id))),
+                ..Node::default()
+            },
+            ModuleType::Conjunction => Node {
+                shape: Shape::Diamond,
+                fill: Some(Color::CYAN),
+                label: Some(format!("&{}", name(*id))),
+            },
+            ModuleType::Broadcast if module.outputs.is_empty() => Node {
+                shape: Shape::DoubleCircle,
+                fill: Some(Color::RED),
+                ..Node::default()
+            },
+            ModuleType::Broadcast => Node {
+                shape: Shape::House,
+                fill: Some(Color::YELLOW),
+                ..Node::default()
+            },
+        };
+        dot.node(name(*id), node);
+    }
+    for id in &ids {
+        for output in &network.modules[*id].outputs {
+            dot.edge(name(*id), name(*output), Edge::default());
+        }
+    }
+    Ok(dot)
+}
+
+/// The modules, indexed by the ids of their names. The button has an id but
+/// no module.
+#[derive(Clone)]
+pub struct Network<'a> {
+    names: Interner<'a>,
+    modules: Vec<Module>,
+    button: usize,
+    broadcaster: usize,
+}
+
+pub fn parse(input: &str) -> error::Result<Network<'_>> {
+    let mut names = Interner::new();
+    let mut modules = vec![];
+    let mut get_id = |name, modules: &mut Vec<Module>| {
+        let id = names.intern(name);
+        if id == modules.len() {
+            modules.push(Module::new(id));
+        }
+        id
+    };
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: &str| Error::new(message).at_line(i + 1, line);
+        let (full_name, outputs_str) = line
+            .split_once(" -> ")
+            .ok_or_else(|| error("expected a module like '%a -> b, c'"))?;
+        let (t, name) = match full_name.split_at_checked(1) {
+            _ if full_name == "broadcaster" => (ModuleType::Broadcast, full_name),
+            Some(("%", name)) if !name.is_empty() => (ModuleType::Flipflop, name),
+            Some(("&", name)) if !name.is_empty() => (ModuleType::Conjunction, name),
+            _ => return Err(error("expected '%' or '&' and a name, or 'broadcaster'")),
+        };
+        let id = get_id(name, &mut modules);
+        modules[id].t = t;
+
+        for output in outputs_str.split(", ") {
+            let output = get_id(output, &mut modules);
+            modules[id].add_output(output);
+            modules[output].add_input(id);
+        }
+    }
+    let broadcaster = names.intern("broadcaster");
+    let button = names.intern("button");
+    Ok(Network {
+        names,
+        modules,
+        button,
+        broadcaster,
+    })
+}
+
+fn process_signal(network: &mut Network) -> (usize, usize) {
+    let mut low = 0;
+    let mut high = 0;
+    let mut queue = VecDeque::new();
+    queue.push_back((network.button, network.broadcaster, Signal::Low));
+    while let Some((src, dst, typ)) = queue.pop_front() {
+        match typ {
+            Signal::Low => low += 1,
+            Signal::High => high += 1,
+        }
+
+        if let Some(module) = network.modules.get_mut(dst) {
+            queue.extend(module.process(src, typ));
+        }
+    }
+    (low, high)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Low,
+    High,
+}
+
+impl Signal {
+    fn flip(&self) -> Self {
+        match self {
+            Signal::Low => Signal::High,
+            Signal::High => Signal::Low,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ModuleType {
+    Broadcast,
+    Flipflop,
+    Conjunction,
+}
+
+#[derive(Clone)]
+struct Module {
+    id: usize,
+    t: ModuleType,
+    inputs: Vec<usize>,
+    outputs: Vec<usize>,
+    is_on: bool,
+    input_high_signals: HashSet<usize>,
+}
+
+impl Module {
+    fn new(id: usize) -> Self {
+        Self {
+            id,
+            t: ModuleType::Broadcast,
+            inputs: vec![],
+            outputs: vec![],
+            is_on: false,
+            input_high_signals: HashSet::new(),
+        }
+    }
+
+    fn add_input(&mut self, input: usize) {
+        self.inputs.push(input)
+    }
+
+    fn add_output(&mut self, output: usize) {
+        self.outputs.push(output)
+    }
+
+    fn process(&mut self, src: usize, typ: Signal) -> Vec<(usize, usize, Signal)> {
+        match self.t {
+            ModuleType::Broadcast => self
+                .outputs
+                .iter()
+                .map(|out| (self.id, *out, typ))
+                .collect(),
+            ModuleType::Flipflop => match typ {
+                Signal::High => vec![],
+                Signal::Low => {
+                    self.is_on = !self.is_on;
+                    let out_type = if self.is_on {
+                        Signal::High
+                    } else {
+                        Signal::Low
+                    };
+                    self.outputs
+                        .iter()
+                        .map(|out| (self.id, *out, out_type))
+                        .collect()
+                }
+            },
+            ModuleType::Conjunction => {
+                match typ {
+                    Signal::Low => self.input_high_signals.remove(&src),
+                    Signal::High => self.input_high_signals.insert(src),
+                };
+                let out_type = if self.inputs.len() == self.input_high_signals.len() {
+                    Signal::Low
+                } else {
+                    Signal::High
+                };
+                self.outputs
+                    .iter()
+                    .map(|out| (self.id, *out, out_type))
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Goal {
+    module: usize,
+    signal: Signal,
+}
+
+impl Goal {
+    fn new(module: usize, signal: Signal) -> Self {
+        Self { module, signal }
+    }
+
+    fn expand_conjunction_inputs(self, modules: &[Module]) -> Vec<Self> {
+        let m = &modules[self.module];
+        if m.inputs
+            .iter()
+            .all(|i| matches!(modules[*i].t, ModuleType::Conjunction))
+        {
+            m.inputs
+                .iter()
+                .map(|i| Goal::new(*i, self.signal.flip()))
+                .collect()
+        } else {
+            vec![self]
+        }
+    }
+
+    /// The first press of the button after which the goal's module receives
+    /// the goal's pulse, giving up after `MAX_PRESSES`.
+    fn find_period(&self, mut network: Network) -> error::Result<usize> {
+        let mut queue = VecDeque::new();
+        for step in 1..=MAX_PRESSES {
+            queue.push_back((network.button, network.broadcaster, Signal::Low));
+            while let Some((src, dst, typ)) = queue.pop_front() {
+                if (self.module, self.signal) == (dst, typ) {
+                    return Ok(step);
+                }
+
+                if let Some(module) = network.modules.get_mut(dst) {
+                    queue.extend(module.process(src, typ));
+                }
+            }
+        }
+        let signal = match self.signal {
+            Signal::Low => "low",
+            Signal::High => "high",
+        };
+        Err(Error::new(format!(
+            "the module '{}' receives no {} pulse in {} presses",
+            network.names.name(self.module),
+            signal,
+            MAX_PRESSES
+        )))
+    }
+}
+
+/// How many times part 2 presses the button looking for a pulse, far more than
+/// the periods of the counters in real inputs, which are below 4096.
+const MAX_PRESSES: usize = 100_000;
+
+/// Four binary counters of `size` flip-flops (between 2 and 12), which like in
+/// real inputs each reset at their own period and together feed `rx`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let bits = size.clamp(2, 12);
+    let mut names = rng.words(4 * (bits + 2) + 1, 2, &["rx"]).into_iter();
+    let output = names.next().unwrap();
+    let mut lines = vec![];
+    let mut starts = vec![];
+    for _ in 0..4 {
+        // An odd period with the top bit set. When the counter gets there, the
+        // conjunction sends a low pulse and resets it to zero.
+        let period = (1 << (bits - 1)) | rng.below(1 << (bits - 1)) | 1;
+        let flipflops = names.by_ref().take(bits).collect::<Vec<_>>();
+        let conjunction = names.next().unwrap();
+        let inverter = names.next().unwrap();
+        let mut resets = vec![];
+        for (bit, flipflop) in flipflops.iter().enumerate() {
+            let mut outputs = flipflops
+                .get(bit + 1)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            if period >> bit & 1 == 1 {
+                outputs.push(conjunction.clone());
+            }
+            if bit == 0 || period >> bit & 1 == 0 {
+                resets.push(flipflop.clone());
+            }
+            lines.push(format!("%{} -> {}", flipflop, outputs.join(", ")));
+        }
+        resets.push(inverter.clone());
+        lines.push(format!("&{} -> {}", conjunction, resets.join(", ")));
+        lines.push(format!("&{} -> {}", inverter, output));
+        starts.push(flipflops[0].clone());
+    }
+    lines.push(format!("&{} -> rx", output));
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(32000000));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("broadcaster -> a\n%a b").unwrap_err().to_string(),
+            "line 2 '%a b': expected a module like '%a -> b, c'"
+        );
+        assert_eq!(
+            part1("broadcaster -> a\na -> b").unwrap_err().to_string(),
+            "line 2 'a -> b': expected '%' or '&' and a name, or 'broadcaster'"
+        );
+        assert_eq!(
+            part2(INPUT).unwrap_err().message(),
+            "there is no module 'rx'"
+        );
+        assert_eq!(
+            part2("broadcaster -> a\n&rx -> a").unwrap_err().message(),
+            "no module sends pulses to 'rx'"
+        );
+        assert_eq!(
+            part2("broadcaster -> a\n&a -> rx").unwrap_err().message(),
+            "the module 'a' receives no high pulse in 100000 presses"
+        );
+    }
+
+    #[test]
+    fn test_graph() {
+        let dot = graph("broadcaster -> a\n%a -> inv\n&inv -> rx")
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            dot,
+            r##"digraph {
+  node [style=filled, fillcolor="#ffffff"]
+  "a" [shape=box, label="%a"]
+  "broadcaster" [shape=house, fillcolor="#e6be28"]
+  "inv" [shape=diamond, fillcolor="#28aab4", label="&inv"]
+  "rx" [shape=doublecircle, fillcolor="#dc322f"]
+  "a" -> "inv"
+  "broadcaster" -> "a"
+  "inv" -> "rx"
+}
+"##
+        );
+        let dot = graph(INPUT).unwrap().to_string();
+        assert_eq!(dot.matches(" -> ").count(), 7);
+    }
+}
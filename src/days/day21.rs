@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+use std::io;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::{Canvas, Color, Frames, Scene};
+use crate::{BitGrid, Point};
+use num::Integer;
+
+pub fn part1(input: &str, n: usize) -> error::Result<usize> {
+    let garden = parse(input)?;
+    let plots = get_plots(&garden.board);
+    let mut points = BitGrid::new(plots.width(), plots.height());
+    points.insert(garden.start);
+    Ok(get_locations_after(&plots, &points, n).len())
+}
+
+pub fn part2(input: &str, n: usize) -> error::Result<usize> {
+    // Important observation:
+    // first, last, and 'S' rows/columns are all empty.
+    // This means it's easy to calculate where and when we get to the edge (and corners) of the map.
+    // So the process of solving is
+    // 1. count steps from center to edges and corners of the map, and cells occupied
+    // 2. count how long does it takes to "fill" a map from each edge/corner, and cells occupied
+    // 3. count how many maps are filled in every direction
+    // 4. calculate filled maps * occupied, plus the remainder of the steps
+    let Garden { board, start: p } = parse(input)?;
+    let bw = board.len();
+    let bwi = bw as i32 - 1;
+    let plots = get_plots(&board);
+    let mut points = BitGrid::new(plots.width(), plots.height());
+    points.insert(p);
+
+    // Validation of assumptions.
+    if bw != board[0].len() || bw < 3 {
+        return Err(Error::new("the map is not a square of side 3 or more"));
+    }
+    if p.x * 2 + 1 != bw as i32 || p.y * 2 + 1 != bw as i32 {
+        return Err(Error::new("the start is not in the middle of the map"));
+    }
+    let free = |p: Point| p.get(&board) == Some('.');
+    let mut lines = (0..bw as i32).flat_map(|i| {
+        [0, p.y, bwi]
+            .map(|y| Point::new(i, y))
+            .into_iter()
+            .chain([0, p.x, bwi].map(|x| Point::new(x, i)))
+    });
+    if !lines.all(free) {
+        return Err(Error::new(
+            "the edges and the middle row and column of the map are not all plots",
+        ));
+    }
+    if n + 1 < bw {
+        let message = format!("{} steps do not reach the edge of the map", n);
+        return Err(Error::new(message));
+    }
+
+    // count points in even and odd steps
+    let even_steps = (board.len() + board[0].len()) * 2;
+    let even_steps_points = get_locations_after(&plots, &points, even_steps);
+    let odd_steps_points = get_locations_after(&plots, &even_steps_points, 1);
+
+    // count even and odd steps whole maps
+    // A map is entered in the middle of an edge (or at a corner along the diagonals),
+    // and filled bw - 1 + bw / 2 (or 2 * (bw - 1)) steps later, so the maps up to
+    // this radius are whole.
+    let maps_traversed_radius = (n + 1) / bw - 1;
+    // maps at radius X = 4*(x-1) (e.g. 4, 8, 12, 16, 20, 24...)
+    // even radii behave like the origin 1 + 8 + 16 + 24 + 32... = 1 + 8(1+2+..+R) = 1 + 4R(R+1)
+    let even_radii = maps_traversed_radius / 2;
+    let maps_like_origin = 1 + 4 * even_radii * (even_radii + 1);
+    // odd radii behave opposite of origin (4 + 12 + 20... = 4*R + 8(1+2+...R-1) = 4RR
+    let odd_radii = maps_traversed_radius.div_ceil(2);
+    let maps_unlike_origin = 4 * odd_radii * odd_radii;
+
+    let steps_in_full_maps = if n.is_even() {
+        maps_like_origin * even_steps_points.len() + maps_unlike_origin * odd_steps_points.len()
+    } else {
+        maps_like_origin * odd_steps_points.len() + maps_unlike_origin * even_steps_points.len()
+    };
+
+    // Steps left when entering the first partial map along each axis, and
+    // maybe the one after it.
+    let left_n = (n + 1) % bw + bw / 2 - 1;
+    let edges = [
+        Point::new(0, p.y),
+        Point::new(bwi, p.y),
+        Point::new(p.x, 0),
+        Point::new(p.x, bwi),
+    ];
+    let corners = [
+        Point::new(0, 0),
+        Point::new(bwi, 0),
+        Point::new(0, bwi),
+        Point::new(bwi, bwi),
+    ];
+    let count = |starts: &[Point], steps: usize| {
+        starts
+            .iter()
+            .map(|start| get_locations_after_p(&plots, *start, steps).len())
+            .sum::<usize>()
+    };
+    let mut steps_part = count(&edges, left_n);
+    if let Some(left_n2) = left_n.checked_sub(bw) {
+        steps_part += count(&edges, left_n2);
+    }
+
+    // Along the diagonals, each quadrant has maps_traversed_radius partial maps
+    // right after the whole ones, and maybe one more band of them after that.
+    let diag1_n = (n + 1) % bw + bw - 2;
+    steps_part += count(&corners, diag1_n) * maps_traversed_radius;
+    if let Some(diag2_n) = diag1_n.checked_sub(bw) {
+        steps_part += count(&corners, diag2_n) * (maps_traversed_radius + 1);
+    }
+
+    Ok(steps_in_full_maps + steps_part)
+}
+
+/// The plots and rocks of the garden, and where the elf starts on a plot.
+pub struct Garden {
+    board: Vec<Vec<char>>,
+    start: Point,
+}
+
+pub fn parse(input: &str) -> error::Result<Garden> {
+    let mut board = error::parse_grid(input, |c| match c {
+        '.' | '#' | 'S' => Ok(c),
+        _ => Err(Error::new(format!("invalid tile '{}'", c))),
+    })?;
+    let start = match Point::find_multiple_in_board('S', &board)[..] {
+        [] => return Err(Error::new("there is no start 'S'")),
+        [start] => start,
+        _ => return Err(Error::new("there is more than one start 'S'")),
+    };
+    start.set(&mut board, '.');
+    Ok(Garden { board, start })
+}
+
+/// Walks the infinitely repeated garden one step at a time, as a reference for
+/// `part2`.
+pub fn part2_reference(input: &str, n: usize) -> usize {
+    let mut board = crate::to_board(input);
+    let start = Point::find_in_board('S', &board);
+    replace(&mut board, 'S', '.');
+    let (height, width) = (board.len() as i32, board[0].len() as i32);
+    let is_plot =
+        |p: &Point| board[p.y.rem_euclid(height) as usize][p.x.rem_euclid(width) as usize] == '.';
+    let mut points = HashSet::from([start]);
+    for _ in 0..n {
+        points = points
+            .iter()
+            .flat_map(|p| [p.up(), p.left(), p.down(), p.right()])
+            .filter(is_plot)
+            .collect();
+    }
+    points.len()
+}
+
+/// Draws the plots reachable after every step on a 5 by 5 tiling of the
+/// garden, from the start in the middle tile until reaching the edge. This is
+/// the expansion that `part2` counts in whole and partial tiles.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let Garden {
+        board: garden,
+        start,
+    } = parse(input)?;
+    let (height, width) = (garden.len(), garden[0].len());
+    let board: Vec<Vec<char>> = (0..5 * height)
+        .map(|y| {
+            (0..5 * width)
+                .map(|x| garden[y % height][x % width])
+                .collect()
+        })
+        .collect();
+    let rocks = Point::find_multiple_in_board('#', &board);
+    let p = Point::new(start.x + 2 * width as i32, start.y + 2 * height as i32);
+    let radius = [
+        p.x,
+        p.y,
+        5 * width as i32 - 1 - p.x,
+        5 * height as i32 - 1 - p.y,
+    ];
+    let plots = get_plots(&board);
+    let mut points = BitGrid::new(plots.width(), plots.height());
+    points.insert(p);
+    let mut frames = Frames::new(canvas);
+    let steps = *radius.iter().min().unwrap() as usize;
+    walk(&plots, &points, steps, |points| {
+        frames.draw(|| {
+            Scene::new(&board)
+                .points(rocks.iter().copied(), Color::GREY)
+                .points(points.iter(), Color::GREEN)
+        })
+    });
+    frames.result()
+}
+
+fn replace(board: &mut [Vec<char>], old: char, new: char) {
+    Point::find_in_board(old, board).set(board, new);
+}
+
+fn get_plots(board: &[Vec<char>]) -> BitGrid {
+    BitGrid::from_board(board, |c| *c != '#')
+}
+
+fn step(plots: &BitGrid, points: &BitGrid) -> BitGrid {
+    &points.neighbours() & plots
+}
+
+fn get_locations_after(plots: &BitGrid, points: &BitGrid, steps: usize) -> BitGrid {
+    walk(plots, points, steps, |_| ())
+}
+
+fn get_locations_after_p(plots: &BitGrid, point: Point, steps: usize) -> BitGrid {
+    let mut points = BitGrid::new(plots.width(), plots.height());
+    points.insert(point);
+    get_locations_after(plots, &points, steps)
+}
+
+/// The plots reachable from `points` after `steps` steps, calling `visit`
+/// with those reachable after every step, the start included.
+fn walk(
+    plots: &BitGrid,
+    points: &BitGrid,
+    steps: usize,
+    mut visit: impl FnMut(&BitGrid),
+) -> BitGrid {
+    let mut points = points.clone();
+    visit(&points);
+    for _ in 0..steps {
+        points = step(plots, &points);
+        visit(&points);
+    }
+    points
+}
+
+/// A garden of side `2 * size + 1` with the start in the middle. Like in real
+/// inputs the edges, the middle row and column and a diamond around the start
+/// are free of rocks, and every plot is reachable.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let half = size.max(2) as i32;
+    let side = 2 * half + 1;
+    let mut board = (0..side)
+        .map(|y| {
+            (0..side)
+                .map(|x| {
+                    let free = [0, half, side - 1].contains(&x)
+                        || [0, half, side - 1].contains(&y)
+                        || ((x - half).abs() + (y - half).abs() - half).abs() <= 1;
+                    if !free && rng.chance(0.1) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let start = Point::new(half, half);
+    let mut reachable = HashSet::from([start]);
+    let mut queue = vec![start];
+    while let Some(p) = queue.pop() {
+        for np in [p.up(), p.left(), p.down(), p.right()] {
+            if np.get(&board) == Some('.') && reachable.insert(np) {
+                queue.push(np);
+            }
+        }
+    }
+    for (y, row) in board.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if !reachable.contains(&Point::new(x as i32, y as i32)) {
+                *cell = '#';
+            }
+        }
+    }
+    start.set(&mut board, 'S');
+    board
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential;
+
+    const INPUT: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT, 6), Ok(16));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("S.\n.x", 1).unwrap_err().to_string(),
+            "line 2 '.x': invalid tile 'x'"
+        );
+        assert_eq!(
+            part1("S.\n.S", 1).unwrap_err().message(),
+            "there is more than one start 'S'"
+        );
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut frames = vec![];
+        visualize(INPUT, &mut frames).unwrap();
+        // From the middle of the 55 by 55 tiling to its edge.
+        assert_eq!(frames.len(), 27 + 1);
+        let reachable = frames[27]
+            .cells()
+            .concat()
+            .iter()
+            .filter(|c| c.bg == Some(Color::GREEN))
+            .count();
+        assert_eq!(reachable, part2_reference(INPUT, 27));
+    }
+
+    #[test]
+    fn test_part2_reference() {
+        assert_eq!(part2_reference(INPUT, 10), 50);
+        assert_eq!(part2_reference(INPUT, 50), 1594);
+        assert_eq!(part2_reference(INPUT, 100), 6536);
+    }
+
+    #[test]
+    fn test_part2_ending_on_a_map_edge() {
+        // No steps are left when entering the partial maps along the axes,
+        // which used to underflow when counting those along the diagonals.
+        let steps = |input: &str| {
+            let bw = input.lines().count();
+            bw / 2 + 3 * bw
+        };
+        differential::compare(
+            21,
+            0..20,
+            2..=4,
+            |input| part2(input, steps(input)).unwrap(),
+            |input| part2_reference(input, steps(input)),
+        )
+        .unwrap_or_else(|mismatch| panic!("{}", mismatch));
+    }
+
+    #[test]
+    fn test_part2_invalid_maps() {
+        let error = |input: &str, n| part2(input, n).unwrap_err().message().to_string();
+        assert_eq!(
+            error("...\n.S.", 10),
+            "the map is not a square of side 3 or more"
+        );
+        assert_eq!(error("S", 10), "the map is not a square of side 3 or more");
+        assert_eq!(
+            error("....\n.S..\n....\n....", 10),
+            "the start is not in the middle of the map"
+        );
+        assert_eq!(
+            error("...\n.S#\n...", 10),
+            "the edges and the middle row and column of the map are not all plots"
+        );
+        assert_eq!(
+            error(".....\n.....\n..S..\n.....\n.....", 3),
+            "3 steps do not reach the edge of the map"
+        );
+        assert_eq!(part2(".....\n.....\n..S..\n.....\n.....", 4), Ok(25));
+    }
+
+    #[test]
+    fn test_part2_matches_reference() {
+        // A few whole maps away, ending anywhere within the last map.
+        for extra in [0, 1, 4, 7, 10] {
+            let steps = |input: &str| {
+                let bw = input.lines().count();
+                bw / 2 + 2 * bw + extra % bw
+            };
+            differential::compare(
+                21,
+                0..10,
+                2..=5,
+                |input| part2(input, steps(input)).unwrap(),
+                |input| part2_reference(input, steps(input)),
+            )
+            .unwrap_or_else(|mismatch| panic!("{}", mismatch));
+        }
+    }
+}
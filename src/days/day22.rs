@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::{Canvas, Color, Frames, Scene};
+use crate::Point;
+use itertools::Itertools;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let bricks = settle(parse(input)?, |_, _| ());
+
+    let supported_by = bricks
+        .iter()
+        .map(|b1| {
+            bricks
+                .iter()
+                .filter(|b2| !b1.collides_with_brick(b2) && b1.down().collides_with_brick(b2))
+                .collect_vec()
+        })
+        .collect_vec();
+    let sole_supporting_bricks = supported_by
+        .iter()
+        .filter(|supporters| supporters.len() == 1)
+        .flatten()
+        .collect::<HashSet<_>>();
+
+    Ok(bricks.len() - sole_supporting_bricks.len())
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let bricks = settle(parse(input)?, |_, _| ());
+
+    let supported_by = bricks
+        .iter()
+        .map(|b1| {
+            bricks
+                .iter()
+                .filter(|b2| !b1.collides_with_brick(b2) && b1.down().collides_with_brick(b2))
+                .collect::<HashSet<_>>()
+        })
+        .collect_vec();
+    let sole_supporting_bricks = supported_by
+        .iter()
+        .filter(|supporters| supporters.len() == 1)
+        .flatten()
+        .collect::<HashSet<_>>();
+
+    let fallen = sole_supporting_bricks
+        .iter()
+        .map(|brick| {
+            let mut bs = HashSet::new();
+            bs.insert(**brick);
+            loop {
+                let mut stop = true;
+                for (b, supported_by) in bricks.iter().zip(&supported_by) {
+                    if !bs.contains(b) && !supported_by.is_empty() && supported_by.is_subset(&bs) {
+                        bs.insert(b);
+                        stop = false;
+                    }
+                }
+                if stop {
+                    break;
+                }
+            }
+            bs.len()
+        })
+        .sum::<usize>();
+    Ok(fallen - sole_supporting_bricks.len())
+}
+
+/// The bricks as they were snapshotted, still falling.
+pub fn parse(input: &str) -> error::Result<Vec<Brick>> {
+    error::parse_lines(input)
+}
+
+/// Lets the bricks fall, lowest first, calling `settled` with the bricks and
+/// how many of them have settled, before the first and after every one.
+fn settle(bricks: Vec<Brick>, mut settled: impl FnMut(&[Brick], usize)) -> Vec<Brick> {
+    let mut bricks = bricks
+        .into_iter()
+        .sorted_by_key(|brick| brick.z1)
+        .collect_vec();
+    let mut heights = Heights::new();
+    settled(&bricks, 0);
+    for i in 0..bricks.len() {
+        while !bricks[i].down().collides_with_heights(&heights) {
+            bricks[i] = bricks[i].down();
+        }
+        bricks[i]
+            .xys()
+            .iter()
+            .for_each(|p| heights.set(*p, bricks[i].z2));
+        settled(&bricks, i + 1);
+    }
+    bricks
+}
+
+/// Draws the bricks from the side, looking along the y axis, after each one
+/// settles: settled bricks in blue, the one that just settled in yellow and
+/// those still in the air in grey.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let bricks = parse(input)?;
+    let width = bricks.iter().map(|b| b.x2 + 1).max().unwrap_or(0);
+    let top = bricks.iter().map(|b| b.z2).max().unwrap_or(0);
+    let mut frames = Frames::new(canvas);
+    settle(bricks, |bricks, settled| {
+        frames.draw(|| side_view(bricks, settled, width, top))
+    });
+    frames.result()
+}
+
+/// The first `settled` bricks have settled. The ground is at the bottom row.
+fn side_view(bricks: &[Brick], settled: usize, width: usize, top: usize) -> Scene {
+    let mut board = vec![vec!['.'; width]; top + 1];
+    board[top] = vec!['-'; width];
+    let cubes = |brick: &Brick| {
+        (brick.x1..=brick.x2)
+            .cartesian_product(brick.z1..=brick.z2)
+            .map(|(x, z)| Point::new(x as i32, (top - z) as i32))
+            .collect_vec()
+    };
+    for brick in bricks {
+        cubes(brick).iter().for_each(|p| p.set(&mut board, '#'));
+    }
+    let (done, falling) = bricks.split_at(settled);
+    let last = done.last().map(cubes).unwrap_or_default();
+    Scene::new(&board)
+        .points(falling.iter().flat_map(cubes), Color::GREY)
+        .points(done.iter().flat_map(cubes), Color::BLUE)
+        .points(last, Color::YELLOW)
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Brick {
+    x1: usize,
+    y1: usize,
+    z1: usize,
+    x2: usize,
+    y2: usize,
+    z2: usize,
+}
+
+impl FromStr for Brick {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let corner = |s: &str| -> error::Result<(usize, usize, usize)> {
+            s.split(',')
+                .map(error::parse::<usize>)
+                .collect::<error::Result<Vec<_>>>()?
+                .into_iter()
+                .collect_tuple()
+                .ok_or_else(|| Error::new(format!("expected three coordinates in '{}'", s)))
+        };
+        let (a, b) = value
+            .split_once('~')
+            .ok_or_else(|| Error::new("expected two corners separated by '~'"))?;
+        let ((x1, y1, z1), (x2, y2, z2)) = (corner(a)?, corner(b)?);
+        let (x1, x2) = crate::minmax(x1, x2);
+        let (y1, y2) = crate::minmax(y1, y2);
+        let (z1, z2) = crate::minmax(z1, z2);
+        Ok(Self {
+            x1,
+            y1,
+            z1,
+            x2,
+            y2,
+            z2,
+        })
+    }
+}
+
+impl Brick {
+    fn xys(&self) -> Vec<Point> {
+        (self.x1..=self.x2)
+            .flat_map(|x| {
+                (self.y1..=self.y2)
+                    .map(|y| Point::new(x as i32, y as i32))
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    fn down(&self) -> Self {
+        Self {
+            x1: self.x1,
+            y1: self.y1,
+            z1: self.z1 - 1,
+            x2: self.x2,
+            y2: self.y2,
+            z2: self.z2 - 1,
+        }
+    }
+
+    fn collides_with_brick(&self, other: &Self) -> bool {
+        self.x1 <= other.x2
+            && self.y1 <= other.y2
+            && self.z1 <= other.z2
+            && self.x2 >= other.x1
+            && self.y2 >= other.y1
+            && self.z2 >= other.z1
+    }
+
+    fn collides_with_heights(&self, heights: &Heights) -> bool {
+        self.xys().iter().any(|p| self.z1 <= heights.get(*p))
+    }
+}
+
+struct Heights(HashMap<Point, usize>);
+
+impl Heights {
+    fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    fn get(&self, p: Point) -> usize {
+        *self.0.get(&p).unwrap_or(&0)
+    }
+
+    fn set(&mut self, p: Point, height: usize) {
+        self.0.insert(p, height);
+    }
+}
+
+/// `size` bricks of up to 4 cubes, in a 10 by 10 column. The second brick is
+/// a cube on top of the first, so removing the first makes it fall.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut occupied = HashSet::new();
+    let mut bricks = vec![];
+    let mut top = None;
+    while bricks.len() < size {
+        let (length, axis, start) = match top.take() {
+            Some(top) => (0, 2, top),
+            None => (
+                rng.below(4),
+                rng.below(3),
+                [rng.below(10), rng.below(10), 1 + rng.below(3 * size)],
+            ),
+        };
+        let cubes = (0..=length)
+            .map(|i| {
+                let mut cube = start;
+                cube[axis] += i;
+                cube
+            })
+            .collect_vec();
+        let end = cubes[length];
+        if end[0] >= 10 || end[1] >= 10 || cubes.iter().any(|c| occupied.contains(c)) {
+            continue;
+        }
+        occupied.extend(cubes);
+        if bricks.is_empty() {
+            top = Some([end[0], end[1], end[2] + 1]);
+        }
+        bricks.push(format!(
+            "{},{},{}~{},{},{}",
+            start[0], start[1], start[2], end[0], end[1], end[2]
+        ));
+    }
+    bricks.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(5));
+    }
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(7));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = part1("1,0,1~1,2,1\n0,0,2~2,0\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 '0,0,2~2,0': expected three coordinates in '2,0'"
+        );
+    }
+
+    #[test]
+    fn test_visualize() {
+        let mut frames = vec![];
+        visualize(INPUT, &mut frames).unwrap();
+        assert_eq!(frames.len(), 1 + 7);
+        let settled = "...\n...\n...\n.#.\n.#.\n###\n#.#\n###\n.#.\n---";
+        assert_eq!(frames[7].board, crate::to_board(settled));
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::render::dot::{Dot, Edge, Node, Shape};
+use crate::render::{Canvas, Color, Scene};
+use crate::{BitGrid, Point};
+use itertools::Itertools;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let board = parse(input)?;
+    let (adj, start, end) = get_graph_with_slopes(&board);
+    Ok(get_longest_path(&adj, start, end, &mut new_seen(&board)))
+}
+
+pub fn part2(input: &str) -> error::Result<usize> {
+    let board = parse(input)?;
+    let (adj, start, end) = get_graph_without_slopes(&board);
+    Ok(get_longest_path(&adj, start, end, &mut new_seen(&board)))
+}
+
+/// The map of the trails, which has at least one path tile for the start and
+/// the end.
+pub fn parse(input: &str) -> error::Result<Vec<Vec<char>>> {
+    let board = error::parse_grid(input, |c| match c {
+        '#' | '.' | '^' | '<' | 'v' | '>' => Ok(c),
+        _ => Err(Error::new(format!("invalid tile '{}'", c))),
+    })?;
+    if !board.iter().flatten().any(|c| *c == '.') {
+        return Err(Error::new("there are no paths"));
+    }
+    Ok(board)
+}
+
+/// Draws the longest hikes of parts 1 and 2, one per frame.
+pub fn visualize(input: &str, canvas: &mut dyn Canvas) -> io::Result<()> {
+    let board = parse(input)?;
+    for (adj, start, end) in [
+        get_graph_with_slopes(&board),
+        get_graph_without_slopes(&board),
+    ] {
+        let route = get_longest_route(&adj, start, end, &mut vec![], &mut new_seen(&board))
+            .unwrap_or_default();
+        canvas.draw(&Scene::new(&board).path(route, Color::RED))?;
+    }
+    Ok(())
+}
+
+/// The junctions of the trail with slopes, joined by the corridors that can be
+/// walked between them, labelled with their lengths.
+pub fn graph(input: &str) -> error::Result<Dot> {
+    let (adj, start, end) = get_graph_with_slopes(&parse(input)?);
+    let junctions = adj
+        .iter()
+        .filter(|(p, next)| **p == start || **p == end || next.len() > 2)
+        .map(|(p, _)| *p)
+        .collect::<HashSet<_>>();
+    let id = |p: Point| format!("{},{}", p.x, p.y);
+
+    let mut dot = Dot::directed();
+    for p in junctions.iter().sorted() {
+        let fill = if *p == start {
+            Some(Color::GREEN)
+        } else if *p == end {
+            Some(Color::RED)
+        } else {
+            None
+        };
+        let shape = if fill.is_some() {
+            Shape::DoubleCircle
+        } else {
+            Shape::Ellipse
+        };
+        dot.node(
+            &id(*p),
+            Node {
+                shape,
+                fill,
+                ..Node::default()
+            },
+        );
+    }
+    for from in junctions.iter().sorted() {
+        for next in &adj[from] {
+            if let Some((to, length)) = walk_corridor(&adj, &junctions, *from, *next) {
+                let label = Some(length.to_string());
+                dot.edge(
+                    &id(*from),
+                    &id(to),
+                    Edge {
+                        label,
+                        ..Edge::default()
+                    },
+                );
+            }
+        }
+    }
+    Ok(dot)
+}
+
+/// Follows the corridor from `from` through `next` to the junction at its other
+/// end, if the slopes allow it, and returns that junction and the corridor's length.
+fn walk_corridor(
+    adj: &HashMap<Point, Vec<Point>>,
+    junctions: &HashSet<Point>,
+    from: Point,
+    next: Point,
+) -> Option<(Point, usize)> {
+    let (mut previous, mut current, mut length) = (from, next, 1);
+    while !junctions.contains(&current) {
+        let step = *adj.get(&current)?.iter().find(|p| **p != previous)?;
+        (previous, current, length) = (current, step, length + 1);
+    }
+    Some((current, length))
+}
+
+type Graph = (HashMap<Point, Vec<Point>>, Point, Point);
+
+fn get_graph_with_slopes(board: &[Vec<char>]) -> Graph {
+    let mut points = Point::find_multiple_in_board('.', board);
+    let mut up = Point::find_multiple_in_board('^', board);
+    let mut left = Point::find_multiple_in_board('<', board);
+    let mut down = Point::find_multiple_in_board('v', board);
+    let mut right = Point::find_multiple_in_board('>', board);
+    let (start, end) = get_start_end(&points);
+
+    let valid = [
+        points.clone(),
+        up.clone(),
+        left.clone(),
+        down.clone(),
+        right.clone(),
+    ]
+    .iter()
+    .flatten()
+    .cloned()
+    .collect::<HashSet<_>>();
+
+    let mut adj = HashMap::new();
+    up.drain(..).for_each(|p| {
+        adj.insert(p, vec![p.up()]);
+    });
+    left.drain(..).for_each(|p| {
+        adj.insert(p, vec![p.left()]);
+    });
+    down.drain(..).for_each(|p| {
+        adj.insert(p, vec![p.down()]);
+    });
+    right.drain(..).for_each(|p| {
+        adj.insert(p, vec![p.right()]);
+    });
+    points.drain(..).for_each(|p| {
+        insert_point(&mut adj, p, &valid);
+    });
+
+    (adj, start, end)
+}
+
+fn get_graph_without_slopes(board: &[Vec<char>]) -> Graph {
+    let board = board
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| if *c == '#' { '#' } else { '.' })
+                .collect_vec()
+        })
+        .collect_vec();
+    let mut points = Point::find_multiple_in_board('.', &board);
+    let (start, end) = get_start_end(&points);
+
+    let valid = points.iter().cloned().collect::<HashSet<_>>();
+
+    let mut adj = HashMap::new();
+    points.drain(..).for_each(|p| {
+        insert_point(&mut adj, p, &valid);
+    });
+
+    (adj, start, end)
+}
+
+fn get_start_end(points: &[Point]) -> (Point, Point) {
+    let start = points.first().unwrap().to_owned();
+    let end = points.last().unwrap().to_owned();
+    (start, end)
+}
+
+fn insert_point(adj: &mut HashMap<Point, Vec<Point>>, p: Point, valid: &HashSet<Point>) {
+    adj.insert(
+        p,
+        [p.up(), p.left(), p.down(), p.right()]
+            .iter()
+            .filter(|pp| valid.contains(*pp))
+            .cloned()
+            .collect_vec(),
+    );
+}
+
+fn new_seen(board: &[Vec<char>]) -> BitGrid {
+    BitGrid::from_board(board, |_| false)
+}
+
+fn get_longest_path(
+    adj: &HashMap<Point, Vec<Point>>,
+    start: Point,
+    end: Point,
+    seen: &mut BitGrid,
+) -> usize {
+    if start == end {
+        seen.len()
+    } else if seen.insert(start) {
+        let result = adj
+            .get(&start)
+            .unwrap()
+            .iter()
+            .map(|neighbor| get_longest_path(adj, *neighbor, end, seen))
+            .max()
+            .unwrap_or(0);
+        seen.remove(&start);
+        result
+    } else {
+        0
+    }
+}
+
+/// Like `get_longest_path`, but returns the route itself, if there is one.
+fn get_longest_route(
+    adj: &HashMap<Point, Vec<Point>>,
+    start: Point,
+    end: Point,
+    route: &mut Vec<Point>,
+    seen: &mut BitGrid,
+) -> Option<Vec<Point>> {
+    if start == end {
+        Some([route.as_slice(), &[end]].concat())
+    } else if seen.insert(start) {
+        route.push(start);
+        let result = adj
+            .get(&start)
+            .unwrap()
+            .iter()
+            .filter_map(|neighbor| get_longest_route(adj, *neighbor, end, route, seen))
+            .max_by_key(|route| route.len());
+        route.pop();
+        seen.remove(&start);
+        result
+    } else {
+        None
+    }
+}
+
+/// A maze of `size` by `size` junctions (at most 5, as the longest path is
+/// found by brute force). Like in real inputs, corridors between junctions
+/// have slopes leading away from the start at both ends.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let n = size.clamp(2, 5);
+    let mut grid_lines = |first: usize| {
+        (0..n)
+            .scan(first, |at, i| {
+                if i > 0 {
+                    *at += 4 + rng.below(5);
+                }
+                Some(*at)
+            })
+            .collect_vec()
+    };
+    let xs = grid_lines(1);
+    let ys = grid_lines(2);
+
+    // A path that only goes right and down makes sure the end is reachable
+    // even when following the slopes.
+    let mut edges = HashSet::new();
+    let (mut i, mut j) = (0, 0);
+    while (i, j) != (n - 1, n - 1) {
+        let next = if j == n - 1 || (i < n - 1 && rng.chance(0.5)) {
+            (i + 1, j)
+        } else {
+            (i, j + 1)
+        };
+        edges.insert(((i, j), next));
+        (i, j) = next;
+    }
+    for i in 0..n {
+        for j in 0..n {
+            if i + 1 < n && rng.chance(0.6) {
+                edges.insert(((i, j), (i + 1, j)));
+            }
+            if j + 1 < n && rng.chance(0.6) {
+                edges.insert(((i, j), (i, j + 1)));
+            }
+        }
+    }
+
+    let width = xs[n - 1] + 2;
+    let height = ys[n - 1] + 4;
+    let mut board = vec![vec!['#'; width]; height];
+    let corridor = |x1: usize, y1: usize, x2: usize, y2: usize| {
+        (y1..=y2).flat_map(move |y| (x1..=x2).map(move |x| Point::new(x as i32, y as i32)))
+    };
+    let entrance = corridor(xs[0], 0, xs[0], ys[0]);
+    let exit = corridor(xs[n - 1], ys[n - 1], xs[n - 1], height - 1);
+    for p in entrance.chain(exit) {
+        p.set(&mut board, '.');
+    }
+    for ((i1, j1), (i2, j2)) in edges {
+        let junctions =
+            [(xs[i1], ys[j1]), (xs[i2], ys[j2])].map(|(x, y)| Point::new(x as i32, y as i32));
+        let slope = if junctions[0].y == junctions[1].y {
+            '>'
+        } else {
+            'v'
+        };
+        for p in corridor(xs[i1], ys[j1], xs[i2], ys[j2]) {
+            let next_to_junction = junctions
+                .iter()
+                .any(|j| j.x.abs_diff(p.x) + j.y.abs_diff(p.y) == 1);
+            p.set(&mut board, if next_to_junction { slope } else { '.' });
+        }
+    }
+    board
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn test_graph() {
+        let dot = graph(INPUT).unwrap().to_string();
+        // The start, the end and seven junctions.
+        assert_eq!(dot.matches("shape=").count(), 9);
+        assert!(dot.contains("\"1,0\" [shape=doublecircle, fillcolor=\"#50a03c\"]"));
+        assert!(dot.contains("\"1,0\" -> \"3,5\" [label=\"15\"]"));
+        assert!(!dot.contains("-> \"1,0\""));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(94));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(154));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("#.#\n#x#").unwrap_err().to_string(),
+            "line 2 '#x#': invalid tile 'x'"
+        );
+        assert_eq!(
+            part2("###\n#>#").unwrap_err().message(),
+            "there are no paths"
+        );
+    }
+
+    #[test]
+    fn test_longest_route() {
+        let board = parse(INPUT).unwrap();
+        let (adj, start, end) = get_graph_with_slopes(&board);
+        let route =
+            get_longest_route(&adj, start, end, &mut vec![], &mut new_seen(&board)).unwrap();
+        assert_eq!(route.len(), 94 + 1);
+        assert_eq!((route[0], route[94]), (start, end));
+        assert!(route
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| adj[a].contains(b)));
+    }
+}
@@ -0,0 +1,318 @@
+use std::ops::{RangeInclusive, Sub};
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::Itertools;
+
+/// The test area of part 1, in both x and y.
+pub const TEST_AREA: RangeInclusive<f64> = 200000000000000f64..=400000000000000f64;
+
+pub fn part1(input: &str, range: RangeInclusive<f64>) -> error::Result<usize> {
+    let hailstones = parse(input)?;
+
+    Ok(hailstones
+        .iter()
+        .tuple_combinations()
+        .filter(|(a, b)| a.intersect_in_range(b, &range))
+        .count())
+}
+
+pub fn part2(input: &str) -> error::Result<i64> {
+    let hailstones = parse(input)?;
+    if hailstones.len() < 2 {
+        return Err(Error::new("expected at least two hailstones"));
+    }
+    let n = 200;
+    let a = hailstones[0];
+    let b = hailstones[1];
+    for vx in (-n)..=n {
+        for vy in (-n)..=n {
+            let rv1 = Hailstone::from_vs(vx as f64, vy as f64, 0f64);
+            let a1 = a - rv1;
+            // Hailstones moving along x as fast as the rock never cross in x and y.
+            let Some((x, _, _, _)) = hailstones
+                .iter()
+                .skip(1)
+                .find_map(|hs: &Hailstone| a1.get_intersection(&(*hs - rv1)))
+            else {
+                continue;
+            };
+            if hailstones
+                .iter()
+                .all(|hs| a1.does_intersect_at_x(&(*hs - rv1), x))
+            {
+                for vz in (-n)..=n {
+                    let rv2 = Hailstone::from_vs(vx as f64, vy as f64, vz as f64);
+                    let a2 = a - rv2;
+                    let b2 = b - rv2;
+                    // Hailstones are hit at whole nanoseconds, rounding avoids
+                    // the float error of positions in the hundreds of trillions.
+                    let Some((_, _, t1, t2)) = a2.get_intersection(&b2) else {
+                        continue;
+                    };
+                    let (t1, t2) = (t1.round(), t2.round());
+                    if ((a2.z + a2.vz * t1) - (b2.z + b2.vz * t2)).abs() < 0.5 {
+                        let x = a.x + a2.vx * t1;
+                        let y = a.y + a2.vy * t1;
+                        let z = a.z + a2.vz * t1;
+                        return Ok((x + y + z) as i64);
+                    }
+                }
+            }
+        }
+    }
+    Err(Error::new(format!(
+        "no rock velocity up to {} in each axis hits every hailstone",
+        n
+    )))
+}
+
+pub fn parse(input: &str) -> error::Result<Vec<Hailstone>> {
+    error::parse_lines(input)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Hailstone {
+    x: f64,
+    y: f64,
+    z: f64,
+    vx: f64,
+    vy: f64,
+    vz: f64,
+}
+
+impl Sub for Hailstone {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            vx: self.vx - rhs.vx,
+            vy: self.vy - rhs.vy,
+            vz: self.vz - rhs.vz,
+        }
+    }
+}
+
+impl FromStr for Hailstone {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let triple = |s: &str| -> error::Result<(f64, f64, f64)> {
+            s.split(',')
+                .map(error::parse::<f64>)
+                .collect::<error::Result<Vec<_>>>()?
+                .into_iter()
+                .collect_tuple()
+                .ok_or_else(|| Error::new(format!("expected three numbers in '{}'", s)))
+        };
+        let (p, v) = value
+            .split_once(" @ ")
+            .ok_or_else(|| Error::new("expected a position and a velocity separated by '@'"))?;
+        let ((x, y, z), (vx, vy, vz)) = (triple(p)?, triple(v)?);
+        Ok(Self {
+            x,
+            y,
+            z,
+            vx,
+            vy,
+            vz,
+        })
+    }
+}
+impl Hailstone {
+    fn from_vs(vx: f64, vy: f64, vz: f64) -> Self {
+        Self {
+            x: 0f64,
+            y: 0f64,
+            z: 0f64,
+            vx,
+            vy,
+            vz,
+        }
+    }
+    fn intersect_in_range(&self, other: &Hailstone, range: &RangeInclusive<f64>) -> bool {
+        if let Some((x, y, t1, t2)) = self.get_intersection(other) {
+            t1 >= 0f64 && t2 >= 0f64 && range.contains(&x) && range.contains(&y)
+        } else {
+            false
+        }
+    }
+
+    fn get_intersection(&self, other: &Hailstone) -> Option<(f64, f64, f64, f64)> {
+        get_intersection_t1_t2(
+            self.x, self.y, self.vx, self.vy, other.x, other.y, other.vx, other.vy,
+        )
+    }
+
+    fn does_intersect_at_x(&self, other: &Hailstone, x: f64) -> bool {
+        if self.vx == 0f64 {
+            return self.x == x;
+        }
+        if other.vx == 0f64 {
+            return other.x == x;
+        }
+        let t1 = (x - self.x) / self.vx;
+        let y1 = self.y + self.vy * t1;
+        let t2 = (x - other.x) / other.vx;
+        let y2 = other.y + other.vy * t2;
+        // Relative to the positions, as their float error grows with them.
+        t1 >= 0f64 && t2 >= 0f64 && (y1 - y2).abs() <= 1e-9 * y1.abs().max(1f64)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_intersection_t1_t2(
+    x1: f64,
+    y1: f64,
+    vx1: f64,
+    vy1: f64,
+    x2: f64,
+    y2: f64,
+    vx2: f64,
+    vy2: f64,
+) -> Option<(f64, f64, f64, f64)> {
+    if vx1 == 0f64 && vx2 == 0f64 {
+        None
+    } else if vx1 == 0f64 {
+        let t2 = (x1 - x2) / vx2;
+        let y = y2 + vy2 * t2;
+        let t1 = (y - y1) / vy1;
+        Some((x1, y, t1, t2))
+    } else if vx2 == 0f64 {
+        let t1 = (x2 - x1) / vx1;
+        let y = y1 + vy1 * t1;
+        let t2 = (y - y2) / vy2;
+        Some((x2, y, t1, t2))
+    } else {
+        // m1 = vy1 / vx1
+        let m1 = vy1 / vx1;
+        let m2 = vy2 / vx2;
+        if m1 == m2 {
+            None
+        } else {
+            // y1 - m1*x1 + m1*x == y2 - m2*x2 + m2*x
+            // x = (y2 - y1 + m1*x1 - m2*x2) / (m1-m2)
+            let x = ((y2 - y1) + m1 * x1 - m2 * x2) / (m1 - m2);
+            // y = y1 - m1*x1 + m1*x
+            let y = y1 - (m1 * x1) + (m1 * x);
+            // x = x1 + vx1*t1
+            // t1 = (x - x1) / vx
+            let t1 = (x - x1) / vx1;
+            let t2 = (x - x2) / vx2;
+            Some((x.round(), y.round(), t1, t2))
+        }
+    }
+}
+
+/// `size` hailstones (at least 3), all hit by a rock thrown from inside the
+/// test area with a velocity of at most 100 in every axis. Hailstones move
+/// faster than 200 along x, so the solver never tries to throw the rock
+/// alongside one of them. The paths of some of them cross in the test area.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let rock = [(); 3].map(|_| rng.range(200_000_000_000_000..400_000_000_000_000));
+    let velocity = [(); 3].map(|_| rng.range(-100..101));
+    loop {
+        let mut times = vec![];
+        while times.len() < size.max(3) {
+            let t = rng.range(100_000_000_000..1_000_000_000_000);
+            if !times.contains(&t) {
+                times.push(t);
+            }
+        }
+        let input = times
+            .iter()
+            .map(|t| {
+                let mut hailstone_velocity = [0; 3];
+                hailstone_velocity[0] = rng.range(201..301) * *rng.choose(&[-1, 1]);
+                for axis in 1..3 {
+                    while [0, velocity[axis]].contains(&hailstone_velocity[axis]) {
+                        hailstone_velocity[axis] = rng.range(-300..301);
+                    }
+                }
+                let position = [0, 1, 2]
+                    .map(|axis| rock[axis] + (velocity[axis] - hailstone_velocity[axis]) * t);
+                format!(
+                    "{} @ {}",
+                    position.iter().join(", "),
+                    hailstone_velocity.iter().join(", ")
+                )
+            })
+            .join("\n");
+        if part1(&input, TEST_AREA).is_ok_and(|crossings| crossings > 0) {
+            return input;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT, 7f64..=27f64), Ok(2));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(47));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = part2("19, 13, 30 @ -2, 1, -2\n18, 19 @ -1, -1, -2").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert!(part2("19, 13, 30 @ -2, 1, -2").is_err());
+    }
+
+    #[test]
+    fn test_part2_rounds_hit_times() {
+        // Generated with seed 13 and size 3, for a rock thrown from a position
+        // whose coordinates sum to 700649874006072.
+        let input = "75896827160846, 107798420387647, 142602384244019 @ 264, 196, 240
+366247748504699, 383421900220389, 451875422565220 @ -210, -259, -270
+464221977562987, 353359365540332, 268646362594244 @ -283, -149, 65";
+        assert_eq!(part2(input), Ok(700649874006072));
+    }
+
+    #[test]
+    fn test_part2_compares_far_positions() {
+        // Generated with seed 42 and size 3: at positions in the hundreds of
+        // trillions, the paths meet within float error, not within 0.001.
+        let input = "129984469173588, 245905452888691, 303561788760258 @ 275, -24, -97
+290803552563309, 221012615855971, 299240971424634 @ -296, 64, -171
+86559460601933, 223204064482266, 231763339383408 @ 262, 29, 41";
+        assert_eq!(part2(input), Ok(767373344931562));
+    }
+
+    #[test]
+    fn test_part2_with_hailstones_as_fast_as_a_tried_rock() {
+        // At the first velocity tried, no two hailstones cross in x and y.
+        let input = "303, 193, 294 @ -200, 5, 7
+506, 202, 294 @ -200, -3, 4
+709, 170, 321 @ -200, 8, -6
+912, 188, 296 @ -200, 1, 2";
+        assert_eq!(part2(input), Ok(600));
+    }
+
+    #[test]
+    fn test_part2_without_solution() {
+        // A generated input, with the last hailstone moved off the rock's path.
+        let input = "168778802460080, 217553917972904, 227437219266755 @ 251, 68, 46
+415294242200258, 297415013939433, 199687288227138 @ -223, -137, 27
+273033366387265, 160106571585679, 232101619838470 @ -242, 288, 14";
+        assert_eq!(
+            part2(input).unwrap_err().to_string(),
+            "no rock velocity up to 200 in each axis hits every hailstone"
+        );
+    }
+}
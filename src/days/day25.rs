@@ -0,0 +1,300 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::interner::Interner;
+use crate::render::dot::{Dot, Edge, Node};
+use crate::render::Color;
+use itertools::Itertools;
+
+pub fn part1(input: &str) -> error::Result<usize> {
+    let g = parse(input)?;
+    let connected_size = g.three_wire_cut()?.len();
+    Ok((connected_size) * (g.len() - connected_size))
+}
+
+/// The wiring diagram, every wire listed once on the line of either end.
+pub fn parse(input: &str) -> error::Result<Graph<'_>> {
+    let mut names = Interner::new();
+    let mut edges = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let (src, dsts) = line.split_once(": ").ok_or_else(|| {
+            Error::new("expected a component like 'jqt: rhn xhk'").at_line(i + 1, line)
+        })?;
+        for dst in dsts.split(' ') {
+            let (src, dst) = (names.intern(src), names.intern(dst));
+            edges.resize(names.len(), vec![]);
+            edges[src].push(dst);
+            edges[dst].push(src);
+        }
+    }
+    Ok(Graph { names, edges })
+}
+
+/// The wiring diagram, with the two groups of components in blue and green,
+/// and the three wires between them in red.
+pub fn graph(input: &str) -> error::Result<Dot> {
+    let g = parse(input)?;
+    let mut side = vec![false; g.len()];
+    g.three_wire_cut()?.into_iter().for_each(|v| side[v] = true);
+    let name = |v: usize| g.names.name(v);
+    let mut dot = Dot::undirected();
+    for v in (0..g.len()).sorted_by_key(|v| name(*v)) {
+        let fill = Some(if side[v] { Color::BLUE } else { Color::GREEN });
+        dot.node(
+            name(v),
+            Node {
+                fill,
+                ..Node::default()
+            },
+        );
+    }
+    for u in (0..g.len()).sorted_by_key(|u| name(*u)) {
+        for v in g
+            .neighbors(u)
+            .iter()
+            .filter(|v| name(u) < name(**v))
+            .sorted_by_key(|v| name(**v))
+        {
+            let color = (side[u] != side[*v]).then_some(Color::RED);
+            dot.edge(
+                name(u),
+                name(*v),
+                Edge {
+                    color,
+                    ..Edge::default()
+                },
+            );
+        }
+    }
+    Ok(dot)
+}
+
+/// The components by the ids of their names, with the ids of their neighbours.
+#[derive(Debug, Clone)]
+pub struct Graph<'a> {
+    names: Interner<'a>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    /// One side of the cut of three wires.
+    fn three_wire_cut(&self) -> error::Result<Vec<usize>> {
+        // The greedy search does not find the cut from every vertex, so try them
+        // in a fixed order until it finds the three wires.
+        (0..self.len())
+            .sorted_by_key(|v| self.names.name(*v))
+            .find_map(|v0| match self.min_cut(v0) {
+                (3, side) => Some(side),
+                _ => None,
+            })
+            .ok_or_else(|| Error::new("no three wires split the components in two"))
+    }
+
+    /// The smallest cut found greedily from `v0`, and the vertices on its side.
+    fn min_cut(&self, v0: usize) -> (usize, Vec<usize>) {
+        let mut min_cut_size = self.neighbors(v0).len();
+        let mut min_cut_connected_size = 1;
+        let mut connected = vec![false; self.len()];
+        connected[v0] = true;
+        let mut order = vec![v0];
+        // The number of wires from the connected vertices to each other one.
+        let mut candidates = vec![0; self.len()];
+        for v1 in self.neighbors(v0) {
+            candidates[*v1] += 1;
+        }
+        let mut cut_size = min_cut_size;
+
+        while order.len() + 1 < self.len() {
+            let candidate = (0..self.len())
+                .filter(|v| !connected[*v])
+                .max_by_key(|v| candidates[*v])
+                .unwrap();
+
+            connected[candidate] = true;
+            order.push(candidate);
+            cut_size -= candidates[candidate];
+            for n in self.neighbors(candidate) {
+                if !connected[*n] {
+                    candidates[*n] += 1;
+                    cut_size += 1;
+                }
+            }
+
+            if min_cut_size > cut_size {
+                min_cut_size = cut_size;
+                min_cut_connected_size = order.len();
+            }
+        }
+        order.truncate(min_cut_connected_size);
+        (min_cut_size, order)
+    }
+
+    fn neighbors(&self, u: usize) -> &[usize] {
+        &self.edges[u]
+    }
+
+    fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    // // Initial approach - iterating over all tuples of 3 edges and checking if they are a min cut.
+    // fn is_connected(&self, a: &str, b: &str, dropped_edges: &HashSet<(&str, &str)>) -> bool {
+    //     let mut seen = HashSet::new();
+    //     let mut queue = vec![a];
+    //     while let Some(u) = queue.pop() {
+    //         if u == b {
+    //             return true;
+    //         }
+    //         if seen.insert(u) {
+    //             for v in self.neighbors(u) {
+    //                 if !dropped_edges.contains(&(u, v)) && !dropped_edges.contains(&(v, u)) {
+    //                     queue.push(v);
+    //                 }
+    //             }
+    //         }
+    //     }
+    //     false
+    // }
+
+    // fn connected_size(&self, a: &str, dropped_edges: &HashSet<(&str, &str)>) -> usize {
+    //     let mut seen = HashSet::new();
+    //     let mut queue = vec![a];
+    //     while let Some(u) = queue.pop() {
+    //         if seen.insert(u) {
+    //             for v in self.neighbors(u) {
+    //                 if !dropped_edges.contains(&(u, v)) && !dropped_edges.contains(&(v, u)) {
+    //                     queue.push(v);
+    //                 }
+    //             }
+    //         }
+    //     }
+    //     seen.len()
+    // }
+
+    // fn vertices(&self) -> Vec<&&str> {
+    //     self.0.keys().collect_vec()
+    // }
+
+    // fn edges(&self) -> Vec<(&str, &str)> {
+    //     let mut e = vec![];
+    //     for u in self.vertices().iter().sorted() {
+    //         e.extend(self.neighbors(u).iter().sorted().map(|v| (**u, *v)))
+    //     }
+    //     e
+    // }
+}
+
+impl<'a> Display for Graph<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (u, neighbors) in self.edges.iter().enumerate() {
+            let neighbors = neighbors.iter().map(|v| self.names.name(*v)).collect_vec();
+            writeln!(f, "{} -> {:?}", self.names.name(u), neighbors).unwrap();
+        }
+        Ok(())
+    }
+}
+
+/// Two groups of components of random sizes around `size`, each too well
+/// connected to be split by cutting three wires, and joined by three wires.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let sizes = [(); 2].map(|_| 5 + rng.below(size.max(1)));
+    let names = rng.words(sizes[0] + sizes[1], 3, &[]);
+    let (a, b) = names.split_at(sizes[0]);
+    let mut wires = BTreeSet::new();
+    let mut connect = |u: &String, v: &String| {
+        if u != v {
+            wires.insert(crate::minmax(u.clone(), v.clone()));
+        }
+    };
+    for group in [a, b] {
+        for (i, u) in group.iter().enumerate() {
+            connect(u, &group[(i + 1) % group.len()]);
+            connect(u, &group[(i + 2) % group.len()]);
+            if rng.chance(0.5) {
+                connect(u, rng.choose(group));
+            }
+        }
+    }
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    rng.shuffle(&mut a);
+    rng.shuffle(&mut b);
+    for i in 0..3 {
+        connect(&a[i], &b[i]);
+    }
+
+    let mut lines = BTreeMap::new();
+    for (u, v) in wires {
+        lines.entry(u).or_insert(vec![]).push(v);
+    }
+    lines
+        .iter()
+        .map(|(u, vs)| format!("{}: {}", u, vs.join(" ")))
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(54));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("a: b\nb c").unwrap_err().to_string(),
+            "line 2 'b c': expected a component like 'jqt: rhn xhk'"
+        );
+        assert_eq!(
+            part1("a: b").unwrap_err().message(),
+            "no three wires split the components in two"
+        );
+    }
+
+    #[test]
+    fn test_part1_when_the_first_vertex_misses_the_cut() {
+        // Generated with seed 72 and size 10: groups of 5 and 7 components.
+        // The greedy search from "elz" ends up with a cut of more wires.
+        let input = "elz: nbe ptw rgf ulr yza
+haf: nbe ptw ulr wek zrz
+jok: jqx qrk wek yza
+jqx: qrk ulr wek yza
+nbe: ptw rgf ulr
+ptw: zrz
+qrk: wek yza
+rgf: ulr zrz
+ulr: zrz
+wek: yza";
+        assert_eq!(part1(input), Ok(35));
+    }
+
+    #[test]
+    fn test_graph() {
+        let dot = graph(INPUT).unwrap().to_string();
+        assert_eq!(dot.matches(" -- ").count(), 33);
+        assert_eq!(dot.matches("color=\"#dc322f\"").count(), 3);
+        assert!(dot.contains("  \"hfx\" -- \"pzl\" [color=\"#dc322f\"]\n"));
+        assert_eq!(dot.matches("fillcolor=\"#2864c8\"").count(), 6);
+    }
+}
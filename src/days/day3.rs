@@ -0,0 +1,306 @@
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::Point;
+use itertools::iproduct;
+
+pub fn part1(input: &str) -> error::Result<u64> {
+    let schematic = parse(input)?;
+    Ok(schematic
+        .part_numbers()
+        .map(|number| number.value as u64)
+        .sum())
+}
+
+pub fn part2(input: &str) -> error::Result<u64> {
+    Ok(parse(input)?.gear_ratios(&GearRule::GEAR).sum())
+}
+
+pub fn parse(input: &str) -> error::Result<Schematic> {
+    input.parse()
+}
+
+/// A number of the schematic, `len` digits from `start` to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub start: Point,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub point: Point,
+}
+
+/// Which gears count, and their ratio: the symbols with exactly `arity`
+/// numbers around them, whose values combine into the ratio.
+pub struct GearRule {
+    pub symbol: char,
+    pub arity: usize,
+    pub combine: fn(&[u32]) -> u64,
+}
+
+impl GearRule {
+    /// The gears of part 2: stars between two numbers, by their product.
+    pub const GEAR: GearRule = GearRule {
+        symbol: '*',
+        arity: 2,
+        combine: |values| values.iter().map(|v| *v as u64).product(),
+    };
+}
+
+/// The numbers and symbols of a schematic, indexed by which touch which,
+/// diagonals included.
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    // By index, the numbers around every symbol and the symbols around every number.
+    around_symbol: Vec<Vec<usize>>,
+    around_number: Vec<Vec<usize>>,
+}
+
+impl FromStr for Schematic {
+    type Err = Error;
+
+    fn from_str(value: &str) -> error::Result<Self> {
+        let board = crate::to_board(value);
+        let mut numbers: Vec<Number> = vec![];
+        let mut symbols = vec![];
+        // The index of the number covering every cell.
+        let mut covering = board
+            .iter()
+            .map(|row| vec![None; row.len()])
+            .collect::<Vec<_>>();
+        for (y, row) in board.iter().enumerate() {
+            for (x, c) in row.iter().enumerate() {
+                let point = Point::new(x as i32, y as i32);
+                match c.to_digit(10) {
+                    Some(digit) => {
+                        let continued = x > 0 && covering[y][x - 1].is_some();
+                        if !continued {
+                            numbers.push(Number {
+                                value: 0,
+                                start: point,
+                                len: 0,
+                            });
+                        }
+                        let number = numbers.last_mut().unwrap();
+                        number.value = number
+                            .value
+                            .checked_mul(10)
+                            .and_then(|n| n.checked_add(digit))
+                            .ok_or_else(|| {
+                                let line = value.lines().nth(y).unwrap_or_default();
+                                Error::new("the number is too large").at_line(y + 1, line)
+                            })?;
+                        number.len += 1;
+                        covering[y][x] = Some(numbers.len() - 1);
+                    }
+                    None if *c != '.' => symbols.push(Symbol { symbol: *c, point }),
+                    None => (),
+                }
+            }
+        }
+        let mut around_symbol = vec![vec![]; symbols.len()];
+        let mut around_number = vec![vec![]; numbers.len()];
+        for (i, symbol) in symbols.iter().enumerate() {
+            let Point { x, y } = symbol.point;
+            for (dx, dy) in iproduct!(-1..=1, -1..=1) {
+                let covered = Point::new(x + dx, y + dy).get(&covering).flatten();
+                // A number can cover several of the neighbours.
+                if let Some(number) = covered.filter(|n| !around_symbol[i].contains(n)) {
+                    around_symbol[i].push(number);
+                    around_number[number].push(i);
+                }
+            }
+        }
+        Ok(Self {
+            numbers,
+            symbols,
+            around_symbol,
+            around_number,
+        })
+    }
+}
+
+impl Schematic {
+    /// The numbers around the symbol with the given index.
+    pub fn numbers_around(&self, symbol: usize) -> impl Iterator<Item = &Number> {
+        self.around_symbol[symbol].iter().map(|n| &self.numbers[*n])
+    }
+
+    /// The symbols around the number with the given index.
+    pub fn symbols_around(&self, number: usize) -> impl Iterator<Item = &Symbol> {
+        self.around_number[number].iter().map(|s| &self.symbols[*s])
+    }
+
+    /// The numbers next to at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.around_number)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// The numbers next to any occurrence of the symbol.
+    pub fn numbers_next_to(&self, symbol: char) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.around_number)
+            .filter(move |(_, symbols)| symbols.iter().any(|s| self.symbols[*s].symbol == symbol))
+            .map(|(number, _)| number)
+    }
+
+    /// The indices of the symbols with at least `k` numbers around them.
+    pub fn symbols_touching(&self, k: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.symbols.len()).filter(move |s| self.around_symbol[*s].len() >= k)
+    }
+
+    /// The ratios of the gears of the rule, in reading order.
+    pub fn gear_ratios<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = u64> + 'a {
+        (0..self.symbols.len())
+            .filter(|s| {
+                self.symbols[*s].symbol == rule.symbol && self.around_symbol[*s].len() == rule.arity
+            })
+            .map(|s| {
+                let values = self.numbers_around(s).map(|n| n.value).collect::<Vec<_>>();
+                (rule.combine)(&values)
+            })
+    }
+}
+
+/// A `size` by `size` schematic with a gear, where there is room for one.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(3);
+    let mut board = vec![vec!['.'; size]; size];
+    for row in board.iter_mut() {
+        let mut x = rng.below(3);
+        while x < size {
+            let len = (1 + rng.below(3)).min(size - x);
+            let number = rng.range(10i64.pow(len as u32 - 1)..10i64.pow(len as u32));
+            for (i, digit) in number.to_string().chars().enumerate() {
+                row[x + i] = digit;
+            }
+            x += len + 1 + rng.below(6);
+        }
+    }
+    for cell in board.iter_mut().flatten() {
+        if *cell == '.' && rng.chance(0.1) {
+            *cell = *rng.choose(&['*', '#', '+', '$', '/', '=', '%', '@', '&', '-']);
+        }
+    }
+    // Numbers by the row and column of their first digit.
+    let number_at = |x: usize, y: usize| {
+        let digits = (0..=x).rev().take_while(|x| board[y][*x].is_ascii_digit());
+        digits.last().map(|start| (y, start))
+    };
+    let gears = iproduct!(0..size, 0..size)
+        .filter(|(x, y)| {
+            let mut numbers = iproduct!(-1..=1, -1..=1)
+                .filter_map(|(dx, dy)| {
+                    let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+                    if nx >= size || ny >= size {
+                        return None;
+                    }
+                    number_at(nx, ny)
+                })
+                .collect::<Vec<_>>();
+            numbers.sort();
+            numbers.dedup();
+            board[*y][*x] == '.' && numbers.len() == 2
+        })
+        .collect::<Vec<_>>();
+    if !gears.is_empty() {
+        let (x, y) = *rng.choose(&gears);
+        board[y][x] = '*';
+    }
+    board
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(4361));
+    }
+
+    #[test]
+    fn test_number_too_large() {
+        let error = part1("..*\n12345678901.").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.message(), "the number is too large");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(467835));
+    }
+
+    #[test]
+    fn test_queries() {
+        let schematic = INPUT.parse::<Schematic>().unwrap();
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        let values = |numbers: Vec<&Number>| numbers.iter().map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(values(schematic.numbers_around(0).collect()), [467, 35]);
+        assert_eq!(
+            values(schematic.numbers_next_to('*').collect()),
+            [467, 35, 617, 755, 598]
+        );
+        assert_eq!(values(schematic.numbers_next_to('#').collect()), [633]);
+        assert_eq!(schematic.symbols_touching(2).count(), 2);
+        assert_eq!(schematic.symbols_touching(1).count(), 6);
+        let number = schematic
+            .numbers
+            .iter()
+            .position(|n| n.value == 617)
+            .unwrap();
+        let symbols = schematic.symbols_around(number).collect::<Vec<_>>();
+        assert_eq!(
+            symbols,
+            [&Symbol {
+                symbol: '*',
+                point: Point::new(3, 4)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_gear_rules() {
+        let schematic = "1.2\n.*.\n3.4\n.+.\n5..".parse::<Schematic>().unwrap();
+        let four = GearRule {
+            arity: 4,
+            combine: |values| values.iter().map(|v| *v as u64).sum(),
+            ..GearRule::GEAR
+        };
+        assert_eq!(schematic.gear_ratios(&four).collect::<Vec<_>>(), [10]);
+        assert_eq!(schematic.gear_ratios(&GearRule::GEAR).count(), 0);
+        let plus = GearRule {
+            symbol: '+',
+            arity: 3,
+            ..GearRule::GEAR
+        };
+        assert_eq!(schematic.gear_ratios(&plus).collect::<Vec<_>>(), [60]);
+    }
+}
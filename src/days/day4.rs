@@ -0,0 +1,242 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use lazy_static::lazy_static;
+use num::{BigUint, One, Zero};
+use regex::Regex;
+
+lazy_static! {
+    static ref CARD_RE: Regex = Regex::new(r"Card\s*(\d+):\s*(.*?)\s*\|\s*(.*)").unwrap();
+    static ref NUMBER_RE: Regex = Regex::new(r"\d+").unwrap();
+}
+
+pub fn part1(input: &str) -> error::Result<BigUint> {
+    let cards = parse(input)?;
+    Ok(points(&cards, doubling))
+}
+
+pub fn part2(input: &str) -> error::Result<BigUint> {
+    let cards = parse(input)?;
+    let copies = copies(&cards, Distribution::Following).map_err(|overflow| {
+        let line = input.lines().nth(overflow.card).unwrap_or_default();
+        let message = "the card wins copies of cards past the end of the table";
+        Error::new(message).at_line(overflow.card + 1, line)
+    })?;
+    Ok(copies.iter().sum())
+}
+
+/// The scratchcards, one per line.
+pub fn parse(input: &str) -> error::Result<Vec<Card>> {
+    error::parse_lines(input)
+}
+
+/// The points of a card with the given number of matches.
+pub type Scoring = fn(usize) -> BigUint;
+
+/// The scoring of part 1: a point for the first match, doubled for every
+/// other one, so big enough numbers for any number of matches.
+pub fn doubling(matches: usize) -> BigUint {
+    match matches {
+        0 => BigUint::zero(),
+        n => BigUint::one() << (n - 1),
+    }
+}
+
+pub fn points(cards: &[Card], scoring: Scoring) -> BigUint {
+    cards.iter().map(|card| scoring(card.matches())).sum()
+}
+
+/// Which cards a card with `n` matches wins a copy of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// The `n` cards after it, which must all be in the table, as in part 2.
+    Following,
+    /// The `n` cards after it, going on from the first card after the last
+    /// one, but at most one copy of each other card and none of itself.
+    /// Copies of cards that were already scratched win nothing.
+    Wrapping,
+    /// The `n` cards after it, or as many as there are.
+    Capped,
+}
+
+/// A card that wins copies of cards past the end of the table, by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub card: usize,
+}
+
+/// How many of every card there are once all of them are scratched, in order,
+/// starting with one of each. Counts can double with every card.
+pub fn copies(cards: &[Card], distribution: Distribution) -> Result<Vec<BigUint>, Overflow> {
+    let len = cards.len();
+    let mut counts = vec![BigUint::from(1u32); len];
+    for (index, card) in cards.iter().enumerate() {
+        let won = match distribution {
+            Distribution::Following if index + card.matches() >= len => {
+                return Err(Overflow { card: index })
+            }
+            Distribution::Wrapping => card.matches().min(len - 1),
+            Distribution::Capped => card.matches().min(len - index - 1),
+            Distribution::Following => card.matches(),
+        };
+        let count = counts[index].clone();
+        for i in 1..=won {
+            counts[(index + i) % len] += &count;
+        }
+    }
+    Ok(counts)
+}
+
+pub struct Card {
+    win_numbers: HashSet<i32>,
+    my_numbers: HashSet<i32>,
+}
+
+impl FromStr for Card {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (_, parts) = CARD_RE
+            .captures(value)
+            .ok_or_else(|| Error::new("expected a card like 'Card 1: 41 48 | 83 86'"))?
+            .extract::<3>();
+        let numbers = |s: &str| {
+            NUMBER_RE
+                .find_iter(s)
+                .map(|n| error::parse::<i32>(n.as_str()))
+                .collect::<error::Result<HashSet<_>>>()
+        };
+        Ok(Card {
+            win_numbers: numbers(parts[1])?,
+            my_numbers: numbers(parts[2])?,
+        })
+    }
+}
+
+impl Card {
+    /// The number of winning numbers that I have.
+    pub fn matches(&self) -> usize {
+        self.win_numbers.intersection(&self.my_numbers).count()
+    }
+}
+
+/// `size` cards, none of them winning more copies than there are cards after it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let format = |numbers: &[i64]| {
+        numbers
+            .iter()
+            .map(|n| format!("{:>2}", n))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    (0..size)
+        .map(|index| {
+            let mut numbers = (1..100).collect::<Vec<_>>();
+            rng.shuffle(&mut numbers);
+            let win_count = 5 + rng.below(6);
+            let my_count = win_count + rng.below(16);
+            let matches = rng.below(win_count.min(size - index - 1) + 1);
+            let win = &numbers[0..win_count];
+            let mut mine = numbers[win_count..win_count + my_count - matches].to_vec();
+            mine.extend(&win[0..matches]);
+            rng.shuffle(&mut mine);
+            format!("Card {:>3}: {} | {}", index + 1, format(win), format(&mine))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let input = "
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+            .trim();
+        assert_eq!(part1(input), Ok(BigUint::from(13u32)));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = "
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+            .trim();
+        assert_eq!(part2(input), Ok(30u32.into()));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = part1("Card 1: 41 48 | 83 86\nCard 2: 13 32 20").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        let error = part2("Card 1: 41 48 | 41 86\nCard 2: 13 32 | 13 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 'Card 2: 13 32 | 13 1': the card wins copies of cards past the end of the table"
+        );
+    }
+
+    #[test]
+    fn test_distributions() {
+        let input = "Card 1: 1 2 | 1 2
+Card 2: 3 | 3
+Card 3: 4 5 | 4 5";
+        let cards = parse(input).unwrap();
+        let counts = |distribution| {
+            copies(&cards, distribution)
+                .map(|counts| counts.iter().map(|c| u64::try_from(c).unwrap()).collect())
+        };
+        assert_eq!(counts(Distribution::Following), Err(Overflow { card: 2 }));
+        assert_eq!(counts(Distribution::Capped), Ok(vec![1, 2, 4]));
+        // Card 3 wins copies of cards 1 and 2, after they were scratched.
+        assert_eq!(counts(Distribution::Wrapping), Ok(vec![5, 6, 4]));
+    }
+
+    #[test]
+    fn test_wrapping_with_more_matches_than_cards() {
+        let counts = |input| {
+            let copies = copies(&parse(input).unwrap(), Distribution::Wrapping).unwrap();
+            copies
+                .iter()
+                .map(|c| u64::try_from(c).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(counts("Card 1: 1 2 | 1 2"), vec![1]);
+        // Card 1 wins one copy of card 2, then both copies of card 2 win one
+        // copy of card 1 each.
+        assert_eq!(
+            counts("Card 1: 1 2 3 | 1 2 3\nCard 2: 4 5 | 4 5"),
+            vec![3, 2]
+        );
+    }
+
+    #[test]
+    fn test_scoring() {
+        let cards = parse("Card 1: 1 2 3 | 1 2 3\nCard 2: 4 | 5").unwrap();
+        assert_eq!(points(&cards, doubling), BigUint::from(4u32));
+        assert_eq!(
+            points(&cards, |matches| BigUint::from(matches * 10)),
+            BigUint::from(30u32)
+        );
+        // 70 matches, past the 64 bits of a u64.
+        let numbers = (1..=70)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let cards = parse(&format!("Card 1: {} | {}", numbers, numbers)).unwrap();
+        assert_eq!(points(&cards, doubling), BigUint::one() << 69);
+    }
+}
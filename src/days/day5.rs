@@ -0,0 +1,749 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cmp;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref TYPES_RE: Regex = Regex::new(r"^(\w+)-to-(\w+) map:$").unwrap();
+}
+
+pub fn part1(input: &str) -> error::Result<u64> {
+    let almanac = parse(input)?;
+    let map = almanac.map_between("seed", "location")?;
+    almanac
+        .seeds
+        .iter()
+        .map(|seed| map.apply(*seed))
+        .min()
+        .ok_or_else(|| Error::new("there are no seeds"))
+}
+
+pub fn part2(input: &str) -> error::Result<u64> {
+    let almanac = parse(input)?;
+    let map = almanac.map_between("seed", "location")?;
+    almanac
+        .seed_ranges()?
+        .into_iter()
+        .filter_map(|range| map.min_over(range))
+        .min()
+        .ok_or_else(|| Error::new("there are no seeds"))
+}
+
+pub fn parse(input: &str) -> error::Result<Almanac> {
+    input.parse()
+}
+
+/// All the maps of the almanac composed into one.
+pub fn seed_to_location(input: &str) -> error::Result<PiecewiseMap> {
+    parse(input)?.map_between("seed", "location")
+}
+
+/// Maps every seed one by one, as a reference for `part2`.
+pub fn part2_reference(input: &str) -> u64 {
+    let mut sections = input.split("\n\n");
+    let seeds = sections
+        .next()
+        .unwrap()
+        .split(' ')
+        .skip(1)
+        .map(crate::parse::<u64>)
+        .collect_vec();
+    let maps = sections
+        .map(|section| {
+            section
+                .lines()
+                .skip(1)
+                .map(|line| line.split(' ').map(crate::parse::<u64>).collect_vec())
+                .collect_vec()
+        })
+        .collect_vec();
+    seeds
+        .chunks(2)
+        .flat_map(|range| range[0]..range[0] + range[1])
+        .map(|seed| {
+            maps.iter().fold(seed, |value, map| {
+                match map.iter().find(|m| m[1] <= value && value < m[1] + m[2]) {
+                    Some(m) => value - m[1] + m[0],
+                    None => value,
+                }
+            })
+        })
+        .min()
+        .unwrap()
+}
+
+/// One line of a map: `length` numbers from `source_start` on are sent to
+/// the numbers from `destination_start` on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub destination_start: u64,
+    pub source_start: u64,
+    pub length: u64,
+}
+
+impl FromStr for Mapping {
+    type Err = Error;
+
+    fn from_str(value: &str) -> error::Result<Self> {
+        let (destination_start, source_start, length) = value
+            .split_whitespace()
+            .map(error::parse::<u64>)
+            .collect::<error::Result<Vec<_>>>()?
+            .into_iter()
+            .collect_tuple()
+            .ok_or_else(|| Error::new("expected a destination, a source and a length"))?;
+        if destination_start.checked_add(length).is_none()
+            || source_start.checked_add(length).is_none()
+        {
+            return Err(Error::new("the range ends past the largest number"));
+        }
+        Ok(Self {
+            destination_start,
+            source_start,
+            length,
+        })
+    }
+}
+
+impl Mapping {
+    fn source_end(&self) -> u64 {
+        self.source_start + self.length
+    }
+
+    fn contains(&self, value: u64) -> bool {
+        self.source_start <= value && value < self.source_end()
+    }
+
+    fn trymap(&self, value: u64) -> Option<u64> {
+        if self.contains(value) {
+            Some(value - self.source_start + self.destination_start)
+        } else {
+            None
+        }
+    }
+}
+
+/// A map of the numbers below `u64::MAX` made of increasing pieces, one per
+/// `Mapping`. The pieces are sorted and cover the whole domain, with identity
+/// pieces between the mappings of the almanac.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseMap {
+    pieces: Vec<Mapping>,
+}
+
+impl PiecewiseMap {
+    pub fn identity() -> Self {
+        Self {
+            pieces: vec![Mapping {
+                destination_start: 0,
+                source_start: 0,
+                length: u64::MAX,
+            }],
+        }
+    }
+
+    /// The map of an almanac section. Where mappings overlap, the first one
+    /// listed wins, as it does when looking them up in order.
+    pub fn new(mappings: &[Mapping]) -> Self {
+        let mut cuts = mappings
+            .iter()
+            .flat_map(|m| [m.source_start, m.source_end()])
+            .chain([0, u64::MAX])
+            .collect_vec();
+        cuts.sort();
+        cuts.dedup();
+        Self::merged(cuts.iter().tuple_windows().map(|(&start, &end)| {
+            let destination_start = mappings
+                .iter()
+                .find_map(|m| m.trymap(start))
+                .unwrap_or(start);
+            Mapping {
+                destination_start,
+                source_start: start,
+                length: end - start,
+            }
+        }))
+    }
+
+    /// Joins the neighbouring pieces that continue each other.
+    fn merged(pieces: impl IntoIterator<Item = Mapping>) -> Self {
+        let mut merged: Vec<Mapping> = vec![];
+        for piece in pieces.into_iter().filter(|piece| piece.length > 0) {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source_end() == piece.source_start
+                        && last.destination_start + last.length == piece.destination_start =>
+                {
+                    last.length += piece.length
+                }
+                _ => merged.push(piece),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    pub fn pieces(&self) -> &[Mapping] {
+        &self.pieces
+    }
+
+    /// The numbers where a new piece starts.
+    pub fn breakpoints(&self) -> impl Iterator<Item = u64> + '_ {
+        self.pieces[1..].iter().map(|piece| piece.source_start)
+    }
+
+    fn piece_at(&self, value: u64) -> &Mapping {
+        let i = self
+            .pieces
+            .partition_point(|piece| piece.source_start <= value);
+        &self.pieces[i - 1]
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        self.piece_at(value).trymap(value).unwrap_or(value)
+    }
+
+    /// The map applying `self`, then `next`.
+    pub fn compose(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces = vec![];
+        for piece in &self.pieces {
+            let end = piece.destination_start + piece.length;
+            let mut value = piece.destination_start;
+            while value < end {
+                let next_piece = next.piece_at(value);
+                let length = cmp::min(end, next_piece.source_end()) - value;
+                pieces.push(Mapping {
+                    destination_start: next_piece.trymap(value).unwrap(),
+                    source_start: piece.source_start + (value - piece.destination_start),
+                    length,
+                });
+                value += length;
+            }
+        }
+        pieces.sort_by_key(|piece| piece.source_start);
+        Self::merged(pieces)
+    }
+
+    /// The inverse map, if every number is the image of exactly one number.
+    pub fn invert(&self) -> Option<PiecewiseMap> {
+        let mut pieces = self
+            .pieces
+            .iter()
+            .map(|piece| Mapping {
+                destination_start: piece.source_start,
+                source_start: piece.destination_start,
+                length: piece.length,
+            })
+            .collect_vec();
+        pieces.sort_by_key(|piece| piece.source_start);
+        let mut end = 0;
+        for piece in &pieces {
+            if piece.source_start != end {
+                return None;
+            }
+            end = piece.source_end();
+        }
+        (end == u64::MAX).then(|| Self::merged(pieces))
+    }
+
+    /// The numbers that map to `value`, smallest first.
+    pub fn preimages(&self, value: u64) -> Vec<u64> {
+        let mut preimages = self
+            .pieces
+            .iter()
+            .filter(|piece| {
+                piece.destination_start <= value && value - piece.destination_start < piece.length
+            })
+            .map(|piece| value - piece.destination_start + piece.source_start)
+            .collect_vec();
+        preimages.sort();
+        preimages
+    }
+
+    /// The smallest image of the numbers in `range`, looking at each piece
+    /// once since every piece is increasing.
+    pub fn min_over(&self, range: Range<u64>) -> Option<u64> {
+        if range.is_empty() {
+            return None;
+        }
+        let first = self
+            .pieces
+            .partition_point(|piece| piece.source_end() <= range.start);
+        self.pieces[first..]
+            .iter()
+            .take_while(|piece| piece.source_start < range.end)
+            .map(|piece| self.apply(cmp::max(piece.source_start, range.start)))
+            .min()
+    }
+}
+
+/// A map of the almanac, from one category to another.
+#[derive(Debug)]
+struct Map {
+    source: usize,
+    destination: usize,
+    line: usize,
+    map: PiecewiseMap,
+}
+
+/// A line of the input, with its number and what it says.
+type Numbered<'a, T> = (usize, &'a str, T);
+
+/// The seeds, and the maps between categories. Every category may have maps
+/// to and from any number of others, as long as they do not form a cycle.
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    categories: Vec<String>,
+    maps: Vec<Map>,
+}
+
+impl FromStr for Almanac {
+    type Err = Error;
+
+    fn from_str(input: &str) -> error::Result<Self> {
+        let mut lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let seeds = match lines.next() {
+            Some((_, line)) if line.starts_with("seeds:") => line["seeds:".len()..]
+                .split_whitespace()
+                .map(error::parse::<u64>)
+                .collect::<error::Result<Vec<_>>>()
+                .map_err(|error| error.at_line(1, line))?,
+            line => {
+                let line = line.map_or("", |(_, line)| line);
+                return Err(Error::new("expected the seeds").at_line(1, line));
+            }
+        };
+        let mut almanac = Almanac {
+            seeds,
+            categories: vec![],
+            maps: vec![],
+        };
+        let mut sections: Vec<Numbered<Vec<Numbered<Mapping>>>> = vec![];
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            if TYPES_RE.is_match(line) {
+                sections.push((number, line, vec![]));
+            } else {
+                let mapping = line
+                    .parse()
+                    .map_err(|error: Error| error.at_line(number, line))?;
+                match sections.last_mut() {
+                    Some((_, _, mappings)) => mappings.push((number, line, mapping)),
+                    None => {
+                        let error = Error::new("expected a map header like 'seed-to-soil map:'");
+                        return Err(error.at_line(number, line));
+                    }
+                }
+            }
+        }
+        for (number, header, mut mappings) in sections {
+            let capture = TYPES_RE.captures(header).unwrap();
+            let source = almanac.category(&capture[1]);
+            let destination = almanac.category(&capture[2]);
+            if almanac
+                .maps
+                .iter()
+                .any(|map| map.source == source && map.destination == destination)
+            {
+                let error = Error::new("there already is a map between these categories");
+                return Err(error.at_line(number, header));
+            }
+            mappings.sort_by_key(|(_, _, mapping)| mapping.source_start);
+            for ((first, _, a), (second, line, b)) in mappings.iter().tuple_windows() {
+                if a.source_end() > b.source_start {
+                    let error = Error::new(format!("the source range overlaps line {}", first));
+                    return Err(error.at_line(*second, line));
+                }
+            }
+            let mappings = mappings
+                .iter()
+                .map(|(_, _, mapping)| *mapping)
+                .collect_vec();
+            almanac.maps.push(Map {
+                source,
+                destination,
+                line: number,
+                map: PiecewiseMap::new(&mappings),
+            });
+        }
+        almanac.check_acyclic()?;
+        Ok(almanac)
+    }
+}
+
+impl Almanac {
+    fn category(&mut self, name: &str) -> usize {
+        match self.categories.iter().position(|category| category == name) {
+            Some(index) => index,
+            None => {
+                self.categories.push(name.to_string());
+                self.categories.len() - 1
+            }
+        }
+    }
+
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.categories.iter().map(String::as_str)
+    }
+
+    /// The seed numbers taken in pairs, as ranges.
+    pub fn seed_ranges(&self) -> error::Result<Vec<Range<u64>>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(Error::new("the seed numbers do not come in pairs"));
+        }
+        self.seeds
+            .chunks(2)
+            .map(|pair| match pair[0].checked_add(pair[1]) {
+                Some(end) => Ok(pair[0]..end),
+                None => Err(Error::new("a seed range ends past the largest number")),
+            })
+            .collect()
+    }
+
+    fn check_acyclic(&self) -> error::Result<()> {
+        // 0: not visited yet, 1: on the current path, 2: done.
+        fn visit(almanac: &Almanac, category: usize, state: &mut [u8]) -> Option<usize> {
+            state[category] = 1;
+            for map in almanac.maps.iter().filter(|map| map.source == category) {
+                match state[map.destination] {
+                    0 => {
+                        if let Some(cycle) = visit(almanac, map.destination, state) {
+                            return Some(cycle);
+                        }
+                    }
+                    1 => return Some(map.destination),
+                    _ => {}
+                }
+            }
+            state[category] = 2;
+            None
+        }
+        let mut state = vec![0; self.categories.len()];
+        for category in 0..self.categories.len() {
+            if state[category] == 0 {
+                if let Some(cycle) = visit(self, category, &mut state) {
+                    let message =
+                        format!("the maps form a cycle through '{}'", self.categories[cycle]);
+                    return Err(Error::new(message));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn index(&self, name: &str) -> error::Result<usize> {
+        self.categories
+            .iter()
+            .position(|category| category == name)
+            .ok_or_else(|| Error::new(format!("there is no category '{}'", name)))
+    }
+
+    /// The maps to follow from one category to another, the fewest possible,
+    /// each either forwards or backwards.
+    fn route(&self, from: usize, to: usize) -> Option<Vec<(&Map, bool)>> {
+        let mut previous = vec![None; self.categories.len()];
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut route = vec![];
+                let mut category = to;
+                while category != from {
+                    let (map, forwards): (&Map, bool) = previous[category].unwrap();
+                    route.push((map, forwards));
+                    category = if forwards {
+                        map.source
+                    } else {
+                        map.destination
+                    };
+                }
+                route.reverse();
+                return Some(route);
+            }
+            for map in &self.maps {
+                for (start, end, forwards) in [
+                    (map.source, map.destination, true),
+                    (map.destination, map.source, false),
+                ] {
+                    if start == category && end != from && previous[end].is_none() {
+                        previous[end] = Some((map, forwards));
+                        queue.push_back(end);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The map from one category to another, composing the maps between them
+    /// and inverting those that lead the other way.
+    pub fn map_between(&self, from: &str, to: &str) -> error::Result<PiecewiseMap> {
+        let route = self
+            .route(self.index(from)?, self.index(to)?)
+            .ok_or_else(|| Error::new(format!("no maps lead from '{}' to '{}'", from, to)))?;
+        let mut composed = PiecewiseMap::identity();
+        for (map, forwards) in route {
+            let (source, destination) = (
+                &self.categories[map.source],
+                &self.categories[map.destination],
+            );
+            crate::debug!("{}-to-{} map, forwards: {}", source, destination, forwards);
+            composed = if forwards {
+                composed.compose(&map.map)
+            } else {
+                let inverse = map.map.invert().ok_or_else(|| {
+                    let header = format!("{}-to-{} map:", source, destination);
+                    Error::new("the map cannot be followed backwards, it is not one-to-one")
+                        .at_line(map.line, &header)
+                })?;
+                composed.compose(&inverse)
+            };
+        }
+        Ok(composed)
+    }
+}
+
+/// An almanac with `size` seed ranges and up to `size` mappings per map. Numbers
+/// are below 2^32, or fewer bits for small sizes, so that every seed of a small
+/// almanac can be checked one by one.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let limit: i64 = 1 << (4 * size).clamp(8, 32);
+    let types = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let seeds = (0..size)
+        .map(|_| {
+            let start = rng.range(0..limit / 2);
+            format!("{} {}", start, rng.range(1..limit / 16))
+        })
+        .collect::<Vec<_>>();
+    let mut almanac = format!("seeds: {}", seeds.join(" "));
+    for (source, destination) in types.iter().zip(&types[1..]) {
+        // Cut the numbers into pieces and shuffle the pieces around.
+        let mut cuts = (0..size).map(|_| rng.range(1..limit)).collect::<Vec<_>>();
+        cuts.extend([0, limit]);
+        cuts.sort();
+        cuts.dedup();
+        let mut pieces = cuts
+            .iter()
+            .zip(&cuts[1..])
+            .map(|(start, end)| (*start, end - start))
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut pieces);
+        let mut destination_start = 0;
+        almanac += &format!("\n\n{}-to-{} map:", source, destination);
+        for (source_start, length) in pieces {
+            almanac += &format!("\n{} {} {}", destination_start, source_start, length);
+            destination_start += length;
+        }
+    }
+    almanac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::differential;
+    const TEST_INPUT: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), Ok(35));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), Ok(46));
+    }
+
+    #[test]
+    fn test_piecewise_map() {
+        let almanac = parse(TEST_INPUT).unwrap();
+        let seed_to_soil = almanac.map_between("seed", "soil").unwrap();
+        assert_eq!(seed_to_soil.breakpoints().collect_vec(), [50, 98, 100]);
+        assert_eq!(
+            [0, 49, 50, 97, 98, 99, 100].map(|seed| seed_to_soil.apply(seed)),
+            [0, 49, 52, 99, 50, 51, 100]
+        );
+        assert_eq!(seed_to_soil.preimages(50), [98]);
+        assert_eq!(seed_to_soil.invert().unwrap().apply(99), 97);
+
+        let folded = PiecewiseMap::new(&["0 10 5".parse().unwrap()]);
+        assert_eq!(folded.preimages(2), [2, 12]);
+        assert_eq!(folded.preimages(12), []);
+        assert_eq!(folded.invert(), None);
+
+        let map = seed_to_location(TEST_INPUT).unwrap();
+        assert_eq!(
+            [79, 14, 55, 13].map(|seed| map.apply(seed)),
+            [82, 43, 86, 35]
+        );
+        assert_eq!(map.preimages(46), [82]);
+        assert_eq!(map.min_over(79..93), Some(46));
+        assert_eq!(map.min_over(79..79), None);
+    }
+
+    #[test]
+    fn test_compose_and_invert() {
+        for seed in 0..20 {
+            let input = generate(&mut Rng::new(seed), 4);
+            let almanac = parse(&input).unwrap();
+            let map = almanac.map_between("seed", "location").unwrap();
+            let inverse = map.invert().unwrap();
+            for value in (0..300).chain([u64::MAX - 1]) {
+                let location = almanac
+                    .maps
+                    .iter()
+                    .fold(value, |value, map| map.map.apply(value));
+                assert_eq!(map.apply(value), location);
+                assert_eq!(inverse.apply(location), value);
+                assert_eq!(map.preimages(location), [value]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_routes() {
+        let almanac = parse(TEST_INPUT).unwrap();
+        assert_eq!(
+            almanac.categories().collect_vec(),
+            [
+                "seed",
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+        let back = almanac.map_between("location", "seed").unwrap();
+        assert_eq!(back.apply(46), 82);
+        let across = almanac.map_between("soil", "water").unwrap();
+        assert_eq!(across.apply(81), 81);
+        assert_eq!(
+            almanac.map_between("water", "water").unwrap(),
+            PiecewiseMap::identity()
+        );
+
+        // Soil and water both come from seeds, so soil to water goes back to
+        // the seeds first.
+        let branching: Almanac = "seeds: 1 2
+
+seed-to-soil map:
+10 0 5
+0 10 5
+
+seed-to-water map:
+20 0 5
+0 20 5
+
+seed-to-light map:
+1 0 1"
+            .parse()
+            .unwrap();
+        let soil_to_water = branching.map_between("soil", "water").unwrap();
+        assert_eq!(
+            [12, 2, 30].map(|soil| soil_to_water.apply(soil)),
+            [22, 12, 30]
+        );
+        assert_eq!(
+            branching.map_between("light", "seed").unwrap_err().to_string(),
+            "line 11 'seed-to-light map:': the map cannot be followed backwards, it is not one-to-one"
+        );
+        assert_eq!(
+            branching
+                .map_between("seed", "location")
+                .unwrap_err()
+                .to_string(),
+            "there is no category 'location'"
+        );
+        let split: Almanac = "seeds: 1\n\na-to-b map:\n\nc-to-d map:".parse().unwrap();
+        assert_eq!(
+            split.map_between("a", "d").unwrap_err().to_string(),
+            "no maps lead from 'a' to 'd'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_almanacs() {
+        let error = |input: &str| input.parse::<Almanac>().unwrap_err().to_string();
+        assert_eq!(error("seed: 1"), "line 1 'seed: 1': expected the seeds");
+        assert_eq!(
+            error("seeds: 1\n\na-to-b map:\n1 2"),
+            "line 4 '1 2': expected a destination, a source and a length"
+        );
+        assert_eq!(
+            error("seeds: 1\n\na-to-b map:\n0 10 5\n20 14 3"),
+            "line 5 '20 14 3': the source range overlaps line 4"
+        );
+        assert_eq!(
+            error("seeds: 1\n\na-to-b map:\n\nb-to-a map:"),
+            "the maps form a cycle through 'a'"
+        );
+        assert_eq!(
+            error("seeds: 1\n\na-to-b map:\n\na-to-b map:"),
+            "line 5 'a-to-b map:': there already is a map between these categories"
+        );
+        assert_eq!(
+            part2("seeds: 1 2 3\n\nseed-to-location map:"),
+            Err(Error::new("the seed numbers do not come in pairs"))
+        );
+    }
+
+    #[test]
+    fn test_part2_matches_reference() {
+        differential::compare(
+            5,
+            0..30,
+            1..=4,
+            |input| part2(input).unwrap(),
+            part2_reference,
+        )
+        .unwrap_or_else(|mismatch| panic!("{}", mismatch));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use num::{BigUint, One, Zero};
+use std::ops::RangeInclusive;
+
+pub fn part1(input: &str) -> error::Result<BigUint> {
+    let sheet = parse(input)?;
+    Ok(sheet.races.iter().map(Race::ways_to_win).product())
+}
+
+pub fn part2(input: &str) -> error::Result<BigUint> {
+    Ok(parse(input)?.kerned.ways_to_win())
+}
+
+/// The sheet of paper, read as separate races and, with the bad kerning
+/// fixed, as a single long one.
+pub struct Sheet {
+    pub races: Vec<Race>,
+    pub kerned: Race,
+}
+
+pub fn parse(input: &str) -> error::Result<Sheet> {
+    let races = read_races(input, false)?;
+    let kerned = read_races(input, true)?.remove(0);
+    Ok(Sheet { races, kerned })
+}
+
+/// The races of the input. With `kerning`, the spaces between the numbers
+/// of a line are a mistake and each line is a single number.
+fn read_races(input: &str, kerning: bool) -> error::Result<Vec<Race>> {
+    let numbers = |index: usize, name: &str| -> error::Result<Vec<BigUint>> {
+        let line = input.lines().nth(index).unwrap_or_default();
+        let numbers = line
+            .strip_prefix(name)
+            .and_then(|line| line.strip_prefix(':'))
+            .ok_or_else(|| Error::new(format!("expected the line to start with '{}:'", name)))
+            .and_then(|numbers| match kerning {
+                true => Ok(vec![error::parse(&numbers.replace(' ', ""))?]),
+                false => numbers.split_whitespace().map(error::parse).collect(),
+            });
+        numbers.map_err(|error| error.at_line(index + 1, line))
+    };
+    let times = numbers(0, "Time")?;
+    let records = numbers(1, "Distance")?;
+    if times.len() != records.len() {
+        let message = format!("{} times but {} distances", times.len(), records.len());
+        return Err(Error::new(message));
+    }
+    Ok(times
+        .into_iter()
+        .zip(records)
+        .map(|(time, record)| Race { time, record })
+        .collect())
+}
+
+/// The speed a boat reaches by holding its button for some time.
+pub type Speed = fn(&BigUint) -> BigUint;
+
+/// The boats of the puzzle, which gain one unit of speed per unit of time.
+pub fn linear(hold: &BigUint) -> BigUint {
+    hold.clone()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Race {
+    pub time: BigUint,
+    pub record: BigUint,
+}
+
+impl Race {
+    /// How far a boat goes when its button is held for `hold`.
+    pub fn distance(&self, speed: Speed, hold: &BigUint) -> BigUint {
+        if hold > &self.time {
+            return BigUint::zero();
+        }
+        speed(hold) * (&self.time - hold)
+    }
+
+    fn wins(&self, speed: Speed, hold: &BigUint) -> bool {
+        self.distance(speed, hold) > self.record
+    }
+
+    /// The hold times that beat the record, solving `hold * (time - hold) >
+    /// record` with an integer square root, so exactly.
+    pub fn winning_holds(&self) -> Option<RangeInclusive<BigUint>> {
+        let best = &self.time / 2u32;
+        if !self.wins(linear, &best) {
+            return None;
+        }
+        // The record is beaten, so the discriminant is positive, and the
+        // first winning hold is `(time - sqrt(discriminant)) / 2` give or take
+        // the rounding of both divisions.
+        let discriminant = &self.time * &self.time - &self.record * 4u32;
+        let mut first = (&self.time - discriminant.sqrt()) / 2u32;
+        while !self.wins(linear, &first) {
+            first += 1u32;
+        }
+        while !first.is_zero() && self.wins(linear, &(&first - 1u32)) {
+            first -= 1u32;
+        }
+        let last = &self.time - &first;
+        Some(first..=last)
+    }
+
+    /// The hold times that beat the record with another speed profile, as
+    /// long as the distance only rises and then only falls with the hold time.
+    pub fn winning_holds_with(&self, speed: Speed) -> Option<RangeInclusive<BigUint>> {
+        // The longest distance, where it stops rising.
+        let (mut low, mut high) = (BigUint::zero(), self.time.clone());
+        while low < high {
+            let middle: BigUint = (&low + &high) / 2u32;
+            let next = &middle + 1u32;
+            if self.distance(speed, &middle) < self.distance(speed, &next) {
+                low = next;
+            } else {
+                high = middle;
+            }
+        }
+        let best = low;
+        if !self.wins(speed, &best) {
+            return None;
+        }
+        // The first winning hold before it, and the last one after it.
+        let (mut low, mut high) = (BigUint::zero(), best.clone());
+        while low < high {
+            let middle = (&low + &high) / 2u32;
+            if self.wins(speed, &middle) {
+                high = middle;
+            } else {
+                low = middle + 1u32;
+            }
+        }
+        let first = low;
+        let (mut low, mut high) = (best, self.time.clone());
+        while low < high {
+            let middle = (&low + &high + 1u32) / 2u32;
+            if self.wins(speed, &middle) {
+                low = middle;
+            } else {
+                high = middle - 1u32;
+            }
+        }
+        Some(first..=low)
+    }
+
+    pub fn ways_to_win(&self) -> BigUint {
+        match self.winning_holds() {
+            Some(holds) => holds.end() - holds.start() + BigUint::one(),
+            None => BigUint::zero(),
+        }
+    }
+}
+
+/// `size` races. Part 2 reads their numbers as one number of about `2 * size`
+/// digits.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let races = (0..size.max(1))
+        .map(|_| {
+            let time = rng.range(7..100);
+            let hold = rng.range(1..time);
+            (time, hold * (time - hold) - 1)
+        })
+        .collect::<Vec<_>>();
+    let line = |name: &str, numbers: Vec<i64>| {
+        numbers
+            .iter()
+            .fold(format!("{}:", name), |line, n| format!("{}{:>6}", line, n))
+    };
+    format!(
+        "{}\n{}",
+        line("Time", races.iter().map(|r| r.0).collect()),
+        line("Distance", races.iter().map(|r| r.1).collect())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Time:      7  15   30
+Distance:  9  40  200";
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(BigUint::from(288u32)));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(BigUint::from(71503u32)));
+    }
+
+    fn race(time: u64, record: u64) -> Race {
+        Race {
+            time: time.into(),
+            record: record.into(),
+        }
+    }
+
+    fn holds(range: RangeInclusive<u64>) -> Option<RangeInclusive<BigUint>> {
+        Some(BigUint::from(*range.start())..=BigUint::from(*range.end()))
+    }
+
+    #[test]
+    fn test_winning_holds() {
+        assert_eq!(race(7, 9).winning_holds(), holds(2..=5));
+        assert_eq!(race(30, 200).winning_holds(), holds(11..=19));
+        assert_eq!(race(4, 3).winning_holds(), holds(2..=2));
+        assert_eq!(race(4, 4).winning_holds(), None);
+        assert_eq!(race(3, 2).winning_holds(), None);
+        assert_eq!(race(0, 0).winning_holds(), None);
+        assert_eq!(race(2, 0).winning_holds(), holds(1..=1));
+    }
+
+    #[test]
+    fn test_winning_holds_match_counting() {
+        let cubic: Speed = |hold| hold * hold;
+        for time in 0..40 {
+            // Every record for the linear boats, and some for the cubic ones.
+            let records = (0..time * time / 4 + 2).chain((0..time * time * time / 4).step_by(7));
+            for record in records {
+                let race = race(time, record);
+                for (speed, solve) in [
+                    (linear as Speed, race.winning_holds()),
+                    (linear, race.winning_holds_with(linear)),
+                    (cubic, race.winning_holds_with(cubic)),
+                ] {
+                    let wins = (0..=time)
+                        .filter(|hold| race.wins(speed, &BigUint::from(*hold)))
+                        .collect::<Vec<_>>();
+                    let expected = wins.first().map(|first| *first..=*wins.last().unwrap());
+                    assert_eq!(solve, expected.and_then(holds), "{:?}", race);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_races() {
+        // A record just below the best distance, far past u64.
+        let time = BigUint::from(10u32).pow(40) + 1u32;
+        let half = &time / 2u32;
+        let record = &half * (&time - &half) - 1u32;
+        let race = Race { time, record };
+        assert_eq!(race.winning_holds(), Some(half.clone()..=&half + 1u32));
+        assert_eq!(race.ways_to_win(), BigUint::from(2u32));
+        let input = "Time: 7 15 30 12345678901234567890\nDistance: 9 40 200 98765432109876543210";
+        assert_eq!(
+            part2(input).unwrap().to_string(),
+            "7153012345678901234567863"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("Time: 7 15\nDistance: 9").unwrap_err().to_string(),
+            "2 times but 1 distances"
+        );
+        assert_eq!(
+            part1("Time: 7\nDistanc: 9").unwrap_err().to_string(),
+            "line 2 'Distanc: 9': expected the line to start with 'Distance:'"
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+pub fn part1(input: &str) -> error::Result<u64> {
+    Ruleset::camel_cards().winnings(&parse(input)?)
+}
+
+pub fn part2(input: &str) -> error::Result<u64> {
+    Ruleset::jokers().winnings(&parse(input)?)
+}
+
+pub fn parse(input: &str) -> error::Result<Vec<Deal>> {
+    error::parse_lines(input)
+}
+
+/// A line of the input, whose cards are only read by a ruleset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deal {
+    pub cards: String,
+    pub bid: u32,
+}
+
+impl FromStr for Deal {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (cards, bid) = value
+            .split_whitespace()
+            .collect_tuple()
+            .ok_or_else(|| Error::new("expected cards and a bid"))?;
+        Ok(Self {
+            cards: cards.to_string(),
+            bid: error::parse(bid)?,
+        })
+    }
+}
+
+impl Display for Deal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.cards, self.bid)
+    }
+}
+
+/// A kind of hand: the hand has groups of cards of the same rank at least as
+/// large as `counts`, largest first, and with `flush` all its cards have the
+/// same suit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandType {
+    pub name: String,
+    pub counts: Vec<usize>,
+    pub flush: bool,
+}
+
+impl HandType {
+    pub fn new(name: &str, counts: &[usize]) -> Self {
+        Self {
+            name: name.to_string(),
+            counts: counts.to_vec(),
+            flush: false,
+        }
+    }
+
+    fn matches(&self, groups: &[usize], flush: bool) -> bool {
+        (flush || !self.flush)
+            && self.counts.len() <= groups.len()
+            && self
+                .counts
+                .iter()
+                .zip(groups)
+                .all(|(count, group)| group >= count)
+    }
+}
+
+/// How hands of the same type compare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Card by card, in the order they were dealt, wildcards as themselves.
+    InOrder,
+    /// The largest groups first and the highest ranks first among groups of
+    /// the same size, wildcards as what they stand for, like in poker.
+    ByGroup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    /// The index of the rank in the ruleset, the strongest last.
+    pub rank: usize,
+    pub suit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: Vec<Card>,
+    pub bid: u32,
+}
+
+/// What a hand is worth under a ruleset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    /// The index of the hand type in the ruleset, `None` when none matches.
+    pub hand_type: Option<usize>,
+    /// The cards with every wildcard replaced by the card it stands for.
+    pub resolved: Vec<Card>,
+    tie_break: Vec<usize>,
+}
+
+impl Evaluation {
+    fn key(&self) -> (Option<usize>, &[usize]) {
+        (self.hand_type, &self.tie_break)
+    }
+}
+
+/// The rules of a game of camel cards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ruleset {
+    /// The ranks of the cards, weakest first.
+    pub ranks: String,
+    /// The suits, each written after the rank of a card, or none at all.
+    pub suits: String,
+    /// The ranks that stand for whichever card makes the best hand.
+    pub wildcards: String,
+    /// The hand types, weakest first.
+    pub types: Vec<HandType>,
+    pub tie_break: TieBreak,
+}
+
+impl Ruleset {
+    /// The rules of part 1.
+    pub fn camel_cards() -> Self {
+        Self {
+            ranks: "23456789TJQKA".to_string(),
+            suits: String::new(),
+            wildcards: String::new(),
+            types: vec![
+                HandType::new("high card", &[1]),
+                HandType::new("one pair", &[2]),
+                HandType::new("two pair", &[2, 2]),
+                HandType::new("three of a kind", &[3]),
+                HandType::new("full house", &[3, 2]),
+                HandType::new("four of a kind", &[4]),
+                HandType::new("five of a kind", &[5]),
+            ],
+            tie_break: TieBreak::InOrder,
+        }
+    }
+
+    /// The rules of part 2, where jacks are jokers, the weakest cards.
+    pub fn jokers() -> Self {
+        Self {
+            ranks: "J23456789TQKA".to_string(),
+            wildcards: "J".to_string(),
+            ..Self::camel_cards()
+        }
+    }
+
+    /// Suited cards like `TH` and poker's flushes and tie-breaks, though
+    /// without straights.
+    pub fn poker() -> Self {
+        let mut types = Self::camel_cards().types;
+        let flush = HandType {
+            flush: true,
+            ..HandType::new("flush", &[1])
+        };
+        types.insert(4, flush);
+        Self {
+            suits: "CDHS".to_string(),
+            types,
+            tie_break: TieBreak::ByGroup,
+            ..Self::camel_cards()
+        }
+    }
+
+    fn card(&self, rank: char, suit: Option<char>) -> error::Result<Card> {
+        let rank = self
+            .ranks
+            .chars()
+            .position(|r| r == rank)
+            .ok_or_else(|| Error::new(format!("unknown card '{}'", rank)))?;
+        let suit = match suit {
+            Some(suit) => Some(
+                self.suits
+                    .chars()
+                    .position(|s| s == suit)
+                    .ok_or_else(|| Error::new(format!("unknown suit '{}'", suit)))?,
+            ),
+            None => None,
+        };
+        Ok(Card { rank, suit })
+    }
+
+    /// The hand of a deal like `32T3K 765`, or `3H2DTS3CKD 765` with suits.
+    pub fn hand(&self, deal: &Deal) -> error::Result<Hand> {
+        let cards = if self.suits.is_empty() {
+            deal.cards
+                .chars()
+                .map(|rank| self.card(rank, None))
+                .collect()
+        } else {
+            deal.cards
+                .chars()
+                .chunks(2)
+                .into_iter()
+                .map(|mut card| {
+                    let rank = card.next().unwrap();
+                    let suit = card
+                        .next()
+                        .ok_or_else(|| Error::new(format!("card '{}' has no suit", rank)))?;
+                    self.card(rank, Some(suit))
+                })
+                .collect::<error::Result<_>>()
+        }?;
+        Ok(Hand {
+            cards,
+            bid: deal.bid,
+        })
+    }
+
+    pub fn parse_hand(&self, line: &str) -> error::Result<Hand> {
+        self.hand(&line.parse()?)
+    }
+
+    /// The hands of the deals, which are the lines of the input.
+    pub fn hands(&self, deals: &[Deal]) -> error::Result<Vec<Hand>> {
+        deals
+            .iter()
+            .enumerate()
+            .map(|(i, deal)| {
+                self.hand(deal)
+                    .map_err(|error| error.at_line(i + 1, &deal.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn format(&self, cards: &[Card]) -> String {
+        let mut text = String::new();
+        for card in cards {
+            text.extend(self.ranks.chars().nth(card.rank));
+            text.extend(card.suit.and_then(|suit| self.suits.chars().nth(suit)));
+        }
+        text
+    }
+
+    fn is_wild(&self, card: &Card) -> bool {
+        let rank = self.ranks.chars().nth(card.rank).unwrap();
+        self.wildcards.contains(rank)
+    }
+
+    fn hand_type(&self, cards: &[Card]) -> Option<usize> {
+        let groups = cards
+            .iter()
+            .counts_by(|card| card.rank)
+            .into_values()
+            .sorted()
+            .rev()
+            .collect_vec();
+        let flush = !self.suits.is_empty() && cards.iter().map(|card| card.suit).all_equal();
+        self.types
+            .iter()
+            .rposition(|hand_type| hand_type.matches(&groups, flush))
+    }
+
+    /// Replaces every wildcard with the card that makes the best hand, by
+    /// type and then by tie-break. Each wildcard either joins a rank already
+    /// in the hand or starts a new group, and new groups take the strongest
+    /// ranks not in the hand, the largest group first. The wildcards take the
+    /// suit of the other cards, which only matters when that makes a flush.
+    fn resolve(&self, cards: &[Card]) -> Vec<Card> {
+        let wild = cards
+            .iter()
+            .positions(|card| self.is_wild(card))
+            .collect_vec();
+        if wild.is_empty() {
+            return cards.to_vec();
+        }
+        let fixed = cards
+            .iter()
+            .filter(|card| !self.is_wild(card))
+            .collect_vec();
+        let present = fixed.iter().map(|card| card.rank).unique().collect_vec();
+        let absent = (0..self.ranks.chars().count())
+            .rev()
+            .filter(|rank| !present.contains(rank))
+            .filter(|rank| {
+                !self.is_wild(&Card {
+                    rank: *rank,
+                    suit: None,
+                })
+            })
+            .collect_vec();
+        let suit = match fixed.first() {
+            Some(card) => card.suit,
+            None => (!self.suits.is_empty()).then_some(0),
+        };
+        // The labels below `present.len()` are the ranks in the hand, the
+        // others new groups.
+        let labels = present.len() + wild.len();
+        wild.iter()
+            .map(|_| 0..labels)
+            .multi_cartesian_product()
+            .filter_map(|labels| {
+                let new_groups = labels
+                    .iter()
+                    .filter(|label| **label >= present.len())
+                    .counts()
+                    .into_iter()
+                    .sorted_by_key(|(label, count)| (Reverse(*count), **label))
+                    .map(|(label, _)| *label)
+                    .collect_vec();
+                if new_groups.len() > absent.len() {
+                    return None;
+                }
+                let mut resolved = cards.to_vec();
+                for (i, label) in wild.iter().zip(&labels) {
+                    let rank = match new_groups.iter().position(|group| group == label) {
+                        Some(group) => absent[group],
+                        None => present[*label],
+                    };
+                    resolved[*i] = Card { rank, suit };
+                }
+                Some(resolved)
+            })
+            .max_by_key(|resolved| {
+                let ranks = resolved.iter().map(|card| card.rank).sorted().rev();
+                (
+                    self.hand_type(resolved),
+                    self.tie_break(cards, resolved),
+                    ranks.collect_vec(),
+                )
+            })
+            .unwrap_or_else(|| cards.to_vec())
+    }
+
+    fn tie_break(&self, cards: &[Card], resolved: &[Card]) -> Vec<usize> {
+        match self.tie_break {
+            TieBreak::InOrder => cards.iter().map(|card| card.rank).collect(),
+            TieBreak::ByGroup => resolved
+                .iter()
+                .counts_by(|card| card.rank)
+                .into_iter()
+                .sorted_by_key(|(rank, count)| (*count, *rank))
+                .rev()
+                .flat_map(|(rank, count)| [rank].repeat(count))
+                .collect(),
+        }
+    }
+
+    pub fn evaluate(&self, hand: &Hand) -> Evaluation {
+        let resolved = self.resolve(&hand.cards);
+        Evaluation {
+            hand_type: self.hand_type(&resolved),
+            tie_break: self.tie_break(&hand.cards, &resolved),
+            resolved,
+        }
+    }
+
+    /// The hands with their evaluations, weakest first.
+    pub fn ranked(&self, hands: Vec<Hand>) -> Vec<(Hand, Evaluation)> {
+        let mut ranked = hands
+            .into_iter()
+            .map(|hand| {
+                let evaluation = self.evaluate(&hand);
+                (hand, evaluation)
+            })
+            .collect_vec();
+        ranked.sort_by(|(_, a), (_, b)| a.key().cmp(&b.key()));
+        ranked
+    }
+
+    /// The total winnings of the hands dealt, each winning its bid times its
+    /// rank.
+    pub fn winnings(&self, deals: &[Deal]) -> error::Result<u64> {
+        Ok(self.report(deals)?.iter().map(|row| row.winnings).sum())
+    }
+
+    /// How every hand dealt ranks, weakest first.
+    pub fn report(&self, deals: &[Deal]) -> error::Result<Vec<Row>> {
+        let ranked = self.ranked(self.hands(deals)?);
+        Ok(ranked
+            .into_iter()
+            .enumerate()
+            .map(|(i, (hand, evaluation))| Row {
+                rank: i + 1,
+                hand: self.format(&hand.cards),
+                hand_type: evaluation
+                    .hand_type
+                    .map_or("none".to_string(), |t| self.types[t].name.clone()),
+                played_as: self.format(&evaluation.resolved),
+                bid: hand.bid,
+                winnings: (i as u64 + 1) * hand.bid as u64,
+            })
+            .collect())
+    }
+}
+
+/// A line of the report of a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub rank: usize,
+    pub hand: String,
+    pub hand_type: String,
+    /// The hand with its wildcards replaced.
+    pub played_as: String,
+    pub bid: u32,
+    pub winnings: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Aligned columns and the total.
+    Text,
+    Csv,
+}
+
+pub fn format_report(rows: &[Row], format: Format) -> String {
+    let header = ["rank", "hand", "type", "played as", "bid", "winnings"];
+    let cells = rows.iter().map(|row| {
+        [
+            row.rank.to_string(),
+            row.hand.clone(),
+            row.hand_type.clone(),
+            row.played_as.clone(),
+            row.bid.to_string(),
+            row.winnings.to_string(),
+        ]
+    });
+    let lines = std::iter::once(header.map(String::from)).chain(cells);
+    match format {
+        Format::Csv => lines.map(|line| line.join(",") + "\n").collect(),
+        Format::Text => {
+            let lines = lines.collect_vec();
+            let widths = (0..header.len())
+                .map(|i| lines.iter().map(|line| line[i].len()).max().unwrap())
+                .collect_vec();
+            let mut text = String::new();
+            for line in &lines {
+                let cells = line
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .join("  ");
+                text += cells.trim_end();
+                text += "\n";
+            }
+            let total: u64 = rows.iter().map(|row| row.winnings).sum();
+            text + &format!("total winnings: {}\n", total)
+        }
+    }
+}
+
+/// `size` distinct hands.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let cards = "23456789TJQKA".chars().collect::<Vec<_>>();
+    let mut hands = HashSet::new();
+    let mut lines = vec![];
+    while lines.len() < size.min(100_000) {
+        let hand = (0..5).map(|_| *rng.choose(&cards)).collect::<String>();
+        if hands.insert(hand.clone()) {
+            lines.push(format!("{} {}", hand, rng.range(1..1001)));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(6440));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(5905));
+    }
+
+    fn type_name(ruleset: &Ruleset, hand: &str) -> String {
+        let hand = ruleset.parse_hand(&format!("{} 1", hand)).unwrap();
+        let hand_type = ruleset.evaluate(&hand).hand_type.unwrap();
+        ruleset.types[hand_type].name.clone()
+    }
+
+    #[test]
+    fn test_hand_types() {
+        let camel = Ruleset::camel_cards();
+        let jokers = Ruleset::jokers();
+        for (hand, standard, joker) in [
+            ("32T3K", "one pair", "one pair"),
+            ("KTJJT", "two pair", "four of a kind"),
+            ("T55J5", "three of a kind", "four of a kind"),
+            ("2233J", "two pair", "full house"),
+            ("JJJJJ", "five of a kind", "five of a kind"),
+            ("J2345", "high card", "one pair"),
+        ] {
+            assert_eq!(type_name(&camel, hand), standard, "{}", hand);
+            assert_eq!(type_name(&jokers, hand), joker, "{}", hand);
+        }
+        let hand = jokers.parse_hand("KTJJT 1").unwrap();
+        assert_eq!(jokers.format(&jokers.evaluate(&hand).resolved), "KTTTT");
+        let hand = jokers.parse_hand("JJJJJ 1").unwrap();
+        assert_eq!(jokers.format(&jokers.evaluate(&hand).resolved), "AAAAA");
+    }
+
+    #[test]
+    fn test_report() {
+        let rows = Ruleset::jokers().report(&parse(INPUT).unwrap()).unwrap();
+        assert_eq!(
+            rows[4],
+            Row {
+                rank: 5,
+                hand: "KTJJT".to_string(),
+                hand_type: "four of a kind".to_string(),
+                played_as: "KTTTT".to_string(),
+                bid: 220,
+                winnings: 1100,
+            }
+        );
+        assert_eq!(
+            format_report(&rows[..2], Format::Csv),
+            "rank,hand,type,played as,bid,winnings\n1,32T3K,one pair,32T3K,765,765\n2,KK677,two pair,KK677,28,56\n"
+        );
+        assert_eq!(
+            format_report(&rows[..2], Format::Text),
+            "rank  hand   type      played as  bid  winnings
+1     32T3K  one pair  32T3K      765  765
+2     KK677  two pair  KK677      28   56
+total winnings: 821
+"
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        // Queens are wild too, and rank just above the jokers.
+        let wild = Ruleset {
+            ranks: "JQ23456789TKA".to_string(),
+            wildcards: "JQ".to_string(),
+            ..Ruleset::jokers()
+        };
+        assert_eq!(type_name(&wild, "QJ2K5"), "three of a kind");
+        assert_eq!(
+            wild.winnings(&parse("QJ2K5 10\n22KK5 1\nJ2345 100").unwrap()),
+            Ok(100 + 2 + 30)
+        );
+
+        // Two pair beats three of a kind, so the jokers stand for different
+        // cards.
+        let mut types = Ruleset::camel_cards().types;
+        types.swap(2, 3);
+        types.truncate(4);
+        let two_pair = Ruleset {
+            types,
+            ..Ruleset::jokers()
+        };
+        assert_eq!(type_name(&two_pair, "AKJJ5"), "two pair");
+        let hand = two_pair.parse_hand("AKJJ5 1").unwrap();
+        let resolved = two_pair.evaluate(&hand).resolved;
+        assert_eq!(
+            two_pair.format(&resolved).chars().sorted().join(""),
+            "5AAKK"
+        );
+
+        let poker = Ruleset::poker();
+        assert_eq!(type_name(&poker, "2H5H9HJHKH"), "flush");
+        assert_eq!(type_name(&poker, "2H2S2C9HKH"), "three of a kind");
+        // The full house of threes beats the one of twos, though it starts
+        // with a lower card.
+        assert_eq!(
+            poker.winnings(&parse("KS2H2S2CKH 1\n3H3S3CTHTD 10\n4C5C7C8CAC 100").unwrap()),
+            Ok(100 + 2 + 30)
+        );
+        assert_eq!(
+            parse("2H2S\n2X 1").unwrap_err().to_string(),
+            "line 1 '2H2S': expected cards and a bid"
+        );
+        let deals = parse("2H2S 1\n2H2X 1").unwrap();
+        assert_eq!(
+            poker.hands(&deals).unwrap_err().to_string(),
+            "line 2 '2H2X 1': unknown suit 'X'"
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use crate::answer::Answer;
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use crate::interner::Interner;
+use crate::render::dot::{Dot, Edge, Node};
+use crate::render::Color;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use num::{BigInt, BigUint, Integer, One, Zero};
+use regex::Regex;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref NODE_RE: Regex = Regex::new(r"^(\w{3}) = \((\w{3}), (\w{3})\)$").unwrap();
+}
+
+pub fn part1(input: &str) -> error::Result<Meeting> {
+    let Network {
+        pattern,
+        location_map,
+    } = parse(input)?;
+    let node = |name: &str| {
+        location_map
+            .names
+            .get(name)
+            .ok_or_else(|| Error::new(format!("there is no node '{}'", name)))
+    };
+    let (source, destination) = (node("AAA")?, node("ZZZ")?);
+    let walk = location_map.walk(source, &pattern, |loc| loc == destination);
+    Ok(Meeting::of(&[walk]))
+}
+
+pub fn part2(input: &str) -> error::Result<Meeting> {
+    let Network {
+        pattern,
+        location_map,
+    } = parse(input)?;
+    let sources = location_map.get_initial_locations();
+    if sources.is_empty() {
+        return Err(Error::new("there are no ghosts, no node ends with 'A'"));
+    }
+    let walks = sources
+        .into_iter()
+        .map(|loc| location_map.walk(loc, &pattern, |loc| location_map.is_dest(loc)))
+        .collect_vec();
+    Ok(Meeting::of(&walks))
+}
+
+/// The turns to take and the nodes they lead through.
+pub struct Network<'a> {
+    pattern: Pattern,
+    location_map: LocationMap<'a>,
+}
+
+pub fn parse(input: &str) -> error::Result<Network<'_>> {
+    let mut lines = input.lines();
+    let turns = lines.next().unwrap_or_default();
+    let pattern = turns
+        .parse::<Pattern>()
+        .map_err(|error| error.at_line(1, turns))?;
+    if let Some(line) = lines.next().filter(|line| !line.is_empty()) {
+        return Err(Error::new("expected a blank line after the turns").at_line(2, line));
+    }
+    Ok(Network {
+        pattern,
+        location_map: LocationMap::from_data(input)?,
+    })
+}
+
+/// The left and right turns from every node. Ghosts start at the green nodes
+/// and end at the red ones.
+pub fn graph(input: &str) -> error::Result<Dot> {
+    let location_map = parse(input)?.location_map;
+    let name = |loc: usize| location_map.names.name(loc);
+    let locations = (0..location_map.names.len())
+        .sorted_by_key(|loc| name(*loc))
+        .collect_vec();
+    let mut dot = Dot::directed();
+    for loc in &locations {
+        let fill = if location_map.is_source(*loc) {
+            Some(Color::GREEN)
+        } else if location_map.is_dest(*loc) {
+            Some(Color::RED)
+        } else {
+            None
+        };
+        dot.node(
+            name(*loc),
+            Node {
+                fill,
+                ..Node::default()
+            },
+        );
+    }
+    for loc in &locations {
+        let (left, right) = (location_map.left[*loc], location_map.right[*loc]);
+        let turns = if left == right {
+            vec![(left, "L,R")]
+        } else {
+            vec![(left, "L"), (right, "R")]
+        };
+        for (next, label) in turns {
+            let label = Some(label.to_string());
+            dot.edge(
+                name(*loc),
+                name(next),
+                Edge {
+                    label,
+                    ..Edge::default()
+                },
+            );
+        }
+    }
+    Ok(dot)
+}
+
+struct Pattern {
+    chars: Vec<char>,
+}
+
+impl FromStr for Pattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        if let Some(c) = s.chars().find(|c| !['L', 'R'].contains(c)) {
+            return Err(Error::new(format!("invalid turn '{}'", c)));
+        }
+        if s.is_empty() {
+            return Err(Error::new("expected the left and right turns"));
+        }
+        Ok(Pattern {
+            chars: s.chars().collect(),
+        })
+    }
+}
+
+impl Pattern {
+    fn index(&self, step: u64) -> usize {
+        (step % self.chars.len() as u64) as usize
+    }
+}
+
+/// The steps at which a walk is on one of the nodes it looks for: those of
+/// `hits`, then every `period` steps again at those after `cycle_start`, since
+/// from there on the walk loops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Walk {
+    hits: Vec<u64>,
+    cycle_start: u64,
+    period: u64,
+}
+
+impl Walk {
+    fn is_hit(&self, step: u64) -> bool {
+        let step = match step.checked_sub(self.cycle_start) {
+            Some(after) => self.cycle_start + after % self.period,
+            None => step,
+        };
+        self.hits.binary_search(&step).is_ok()
+    }
+
+    fn cycle_hits(&self) -> impl Iterator<Item = u64> + Clone + '_ {
+        self.hits
+            .iter()
+            .copied()
+            .filter(|hit| *hit >= self.cycle_start)
+    }
+}
+
+/// When all the walks are on the nodes they look for at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Meeting {
+    At(BigUint),
+    Never,
+}
+
+impl From<Meeting> for Answer {
+    fn from(meeting: Meeting) -> Self {
+        match meeting {
+            Meeting::At(steps) => steps.into(),
+            Meeting::Never => Answer::Text("never".to_string()),
+        }
+    }
+}
+
+impl Meeting {
+    fn of(walks: &[Walk]) -> Self {
+        // Until every walk loops, try the steps one by one.
+        let looping = walks.iter().map(|walk| walk.cycle_start).max().unwrap_or(0);
+        if let Some(step) = (0..looping).find(|step| walks.iter().all(|walk| walk.is_hit(*step))) {
+            return Meeting::At(step.into());
+        }
+        // Then every walk is on its nodes at some steps modulo its period, and
+        // the steps where they all are solve those congruences together.
+        let mut congruences = vec![(BigInt::zero(), BigInt::one())];
+        for walk in walks {
+            let period = BigInt::from(walk.period);
+            congruences = congruences
+                .iter()
+                .cartesian_product(walk.cycle_hits())
+                .filter_map(|((residue, modulus), hit)| {
+                    let hit = (BigInt::from(hit), period.clone());
+                    crt((residue.clone(), modulus.clone()), hit)
+                })
+                .unique()
+                .collect();
+            crate::debug!("{} congruences", congruences.len());
+        }
+        let looping = BigInt::from(looping);
+        congruences
+            .into_iter()
+            .map(|(residue, modulus)| {
+                // The first step from `looping` on.
+                &looping + (residue - &looping).mod_floor(&modulus)
+            })
+            .min()
+            .map_or(Meeting::Never, |step| {
+                Meeting::At(step.to_biguint().unwrap())
+            })
+    }
+}
+
+/// The numbers that are congruent to both `a` and `b`, as a residue modulo
+/// the lcm of their moduli, if there are any.
+fn crt(a: (BigInt, BigInt), b: (BigInt, BigInt)) -> Option<(BigInt, BigInt)> {
+    let ((a, m), (b, n)) = (a, b);
+    let gcd = m.extended_gcd(&n);
+    let difference = b - &a;
+    if !(&difference % &gcd.gcd).is_zero() {
+        return None;
+    }
+    let n = n / &gcd.gcd;
+    let k = (difference / &gcd.gcd * gcd.x).mod_floor(&n);
+    let modulus = &m * n;
+    Some(((a + m * k).mod_floor(&modulus), modulus))
+}
+
+/// The turns from every location, by the ids of their names.
+struct LocationMap<'a> {
+    names: Interner<'a>,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl<'a> LocationMap<'a> {
+    fn from_data(data: &'a str) -> error::Result<Self> {
+        let mut names = Interner::new();
+        let items = data
+            .lines()
+            .enumerate()
+            .skip(2)
+            .map(|(i, line)| {
+                let (_, nodes) = NODE_RE
+                    .captures(line)
+                    .ok_or_else(|| {
+                        Error::new("expected a node and its turns like 'AAA = (BBB, CCC)'")
+                            .at_line(i + 1, line)
+                    })?
+                    .extract();
+                Ok((i + 1, line, nodes.map(|name| names.intern(name))))
+            })
+            .collect::<error::Result<Vec<_>>>()?;
+        let mut defined = vec![false; names.len()];
+        let mut left = vec![0; names.len()];
+        let mut right = vec![0; names.len()];
+        for (number, line, [loc, l, r]) in &items {
+            if defined[*loc] {
+                let message = format!("the node '{}' is already defined", names.name(*loc));
+                return Err(Error::new(message).at_line(*number, line));
+            }
+            defined[*loc] = true;
+            left[*loc] = *l;
+            right[*loc] = *r;
+        }
+        // Every node a turn leads to must have turns of its own.
+        for (number, line, [_, l, r]) in &items {
+            if let Some(next) = [l, r].into_iter().find(|next| !defined[**next]) {
+                let message = format!("the node '{}' is not defined", names.name(*next));
+                return Err(Error::new(message).at_line(*number, line));
+            }
+        }
+        Ok(Self { names, left, right })
+    }
+
+    fn is_source(&self, loc: usize) -> bool {
+        self.names.name(loc).ends_with('A')
+    }
+
+    fn is_dest(&self, loc: usize) -> bool {
+        self.names.name(loc).ends_with('Z')
+    }
+
+    fn get_next_location(&self, loc: usize, c: char) -> usize {
+        if c == 'L' {
+            self.left[loc]
+        } else {
+            self.right[loc]
+        }
+    }
+
+    fn get_initial_locations(&self) -> Vec<usize> {
+        (0..self.names.len())
+            .filter(|loc| self.is_source(*loc))
+            .collect()
+    }
+
+    /// Walks from `loc` until it is back on the same node at the same point of
+    /// the pattern, noting the steps at which it is on an `end` node.
+    fn walk(&self, loc: usize, pattern: &Pattern, end: impl Fn(usize) -> bool) -> Walk {
+        let len = pattern.chars.len();
+        let mut seen = vec![None; self.names.len() * len];
+        let (mut curr, mut step) = (loc, 0);
+        let mut hits = vec![];
+        loop {
+            let index = pattern.index(step);
+            if let Some(first) = seen[curr * len + index] {
+                return Walk {
+                    hits,
+                    cycle_start: first,
+                    period: step - first,
+                };
+            }
+            seen[curr * len + index] = Some(step);
+            if end(curr) {
+                hits.push(step);
+            }
+            curr = self.get_next_location(curr, pattern.chars[index]);
+            step += 1;
+        }
+    }
+}
+
+/// A pattern of `size` instructions and six ghosts. Like the real inputs, every
+/// ghost's path is a loop that reaches its `..Z` node after a whole number of
+/// patterns, and continues from there exactly like from its `..A` node.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let pattern = (0..size.max(1))
+        .map(|_| *rng.choose(&['L', 'R']))
+        .collect::<Vec<_>>();
+    let letters = "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars().collect::<Vec<_>>();
+    let mut names = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut name = |rng: &mut Rng, last: Option<char>| loop {
+        // Only sources end with 'A' and only destinations with 'Z'.
+        let last = last.unwrap_or(letters[1 + rng.below(24)]);
+        let name = format!("{}{}{}", rng.choose(&letters), rng.choose(&letters), last);
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut lines = vec![];
+    for (ghost, loops) in [2, 3, 5, 7, 11, 13].iter().enumerate() {
+        let length = pattern.len() * loops;
+        let mut path = vec![];
+        if ghost == 0 {
+            path.push("AAA".to_string());
+        } else {
+            path.push(name(rng, Some('A')));
+        }
+        path.extend((1..length).map(|_| name(rng, None)));
+        if ghost == 0 {
+            path.push("ZZZ".to_string());
+        } else {
+            path.push(name(rng, Some('Z')));
+        }
+
+        // The branch not taken at each step leads anywhere on the path.
+        let others = (0..length)
+            .map(|_| path[rng.below(length)].clone())
+            .collect::<Vec<_>>();
+        for (i, node) in path.iter().enumerate() {
+            // The destination continues like the source.
+            let step = i % length;
+            let (taken, other) = (&path[step + 1], &others[step]);
+            let (left, right) = if pattern[step % pattern.len()] == 'L' {
+                (taken, other)
+            } else {
+                (other, taken)
+            };
+            lines.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+    rng.shuffle(&mut lines);
+    format!(
+        "{}\n\n{}",
+        pattern.iter().collect::<String>(),
+        lines.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        let input = "LR
+
+AAA = (BBB, ZZZ)
+BBB = (ZZZ, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(
+            graph(input).unwrap().to_string(),
+            r##"digraph {
+  node [style=filled, fillcolor="#ffffff"]
+  "AAA" [shape=ellipse, fillcolor="#50a03c"]
+  "BBB" [shape=ellipse]
+  "ZZZ" [shape=ellipse, fillcolor="#dc322f"]
+  "AAA" -> "BBB" [label="L"]
+  "AAA" -> "ZZZ" [label="R"]
+  "BBB" -> "ZZZ" [label="L,R"]
+  "ZZZ" -> "ZZZ" [label="L,R"]
+}
+"##
+        );
+    }
+
+    #[test]
+    fn test_part1_1() {
+        let input = "RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(part1(input), Ok(Meeting::At(2u32.into())));
+    }
+
+    #[test]
+    fn test_part1_2() {
+        let input = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(part1(input), Ok(Meeting::At(6u32.into())));
+        let error = part1("L\n\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!(error.to_string(), "there is no node 'ZZZ'");
+        let unreachable = "L\n\nAAA = (BBB, BBB)\nBBB = (BBB, BBB)\nZZZ = (ZZZ, ZZZ)";
+        assert_eq!(part1(unreachable), Ok(Meeting::Never));
+        let error = part1("L\n\nAAA = (ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3 'AAA = (ZZZ)': expected a node and its turns like 'AAA = (BBB, CCC)'"
+        );
+    }
+
+    #[test]
+    fn test_invalid_turns() {
+        let error = part1("LX\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(error.to_string(), "line 1 'LX': invalid turn 'X'");
+        let error = part1("\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(error.message(), "expected the left and right turns");
+        assert_eq!(part2("").unwrap_err().line(), Some(1));
+        let error = part1("L\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(error.message(), "expected a blank line after the turns");
+    }
+
+    #[test]
+    fn test_part2_without_ghosts() {
+        let error = part2("L\n\nBBB = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "there are no ghosts, no node ends with 'A'"
+        );
+    }
+
+    #[test]
+    fn test_undefined_nodes() {
+        let error = part1("L\n\nZZZ = (ZZZ, ZZZ)\nAAA = (QQQ, QQQ)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4 'AAA = (QQQ, QQQ)': the node 'QQQ' is not defined"
+        );
+        let error = part2("L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n11A = (11A, 11A)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 5 '11A = (11A, 11A)': the node '11A' is already defined"
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert_eq!(part2(input), Ok(Meeting::At(6u32.into())));
+    }
+
+    #[test]
+    fn test_part2_without_aligned_loops() {
+        // The first ghost is on 11Z at steps 1, 4, 7... and the second one
+        // on 22Z at steps 2, 4, 6..., not at the lcm of 1 and 2.
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11C, 11C)
+11C = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22Z, 22Z)";
+        assert_eq!(part2(input), Ok(Meeting::At(4u32.into())));
+        // Odd steps for the first ghost, even ones for the second.
+        let never = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        assert_eq!(part2(never), Ok(Meeting::Never));
+        assert_eq!(Answer::from(Meeting::Never).to_string(), "never");
+        // Both ghosts pass their ..Z node once before looping elsewhere.
+        let before_loops = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22B, 22B)";
+        assert_eq!(part2(before_loops), Ok(Meeting::At(1u32.into())));
+    }
+
+    #[test]
+    fn test_crt() {
+        let congruence = |a: i64, m: i64| (BigInt::from(a), BigInt::from(m));
+        assert_eq!(
+            crt(congruence(2, 3), congruence(3, 5)),
+            Some(congruence(8, 15))
+        );
+        assert_eq!(
+            crt(congruence(1, 4), congruence(3, 6)),
+            Some(congruence(9, 12))
+        );
+        assert_eq!(crt(congruence(1, 4), congruence(2, 6)), None);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::error::{self, Error};
+use crate::generate::Rng;
+use itertools::Itertools;
+
+pub fn part1(input: &str) -> error::Result<i32> {
+    extrapolate(input, deduce_next_value)
+}
+
+pub fn part2(input: &str) -> error::Result<i32> {
+    extrapolate(input, deduce_previous_value)
+}
+
+/// The history of every value, one per line.
+pub fn parse(input: &str) -> error::Result<Vec<Vec<i32>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let values = line
+                .split_whitespace()
+                .map(error::parse)
+                .collect::<error::Result<Vec<_>>>();
+            match values {
+                Ok(values) if values.is_empty() => Err(Error::new("expected some values")),
+                values => values,
+            }
+            .map_err(|error| error.at_line(i + 1, line))
+        })
+        .collect()
+}
+
+fn extrapolate(input: &str, deduce: fn(&[i32]) -> Option<i32>) -> error::Result<i32> {
+    let histories = parse(input)?;
+    input
+        .lines()
+        .zip(&histories)
+        .enumerate()
+        .map(|(i, (line, values))| {
+            deduce(values)
+                .ok_or_else(|| Error::new("the extrapolated value overflows").at_line(i + 1, line))
+        })
+        .sum()
+}
+
+/// The differences between consecutive values, or `None` if one overflows.
+fn derivatives(values: &[i32]) -> Option<Vec<i32>> {
+    values
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b.checked_sub(*a))
+        .collect()
+}
+
+fn deduce_next_value(values: &[i32]) -> Option<i32> {
+    if values.iter().all(|value| *value == 0) {
+        return Some(0);
+    }
+    let diff = deduce_next_value(&derivatives(values)?)?;
+    values.last()?.checked_add(diff)
+}
+
+fn deduce_previous_value(values: &[i32]) -> Option<i32> {
+    if values.iter().all(|value| *value == 0) {
+        return Some(0);
+    }
+    let diff = deduce_previous_value(&derivatives(values)?)?;
+    values.first()?.checked_sub(diff)
+}
+
+/// `size` histories of 21 values, each a polynomial of degree at most 6.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            // Start from the constant differences and integrate up to the values.
+            let degree = rng.below(7);
+            let mut values = vec![rng.range(-5..6); 21];
+            for _ in 0..degree {
+                let mut value = rng.range(-20..21);
+                values = values
+                    .iter()
+                    .map(|difference| {
+                        let current = value;
+                        value += difference;
+                        current
+                    })
+                    .collect();
+            }
+            values.iter().join(" ")
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static INPUT: &str = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(INPUT), Ok(114))
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(INPUT), Ok(2))
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            part1("1 2\n3 x").unwrap_err().to_string(),
+            "line 2 '3 x': cannot parse 'x': invalid digit found in string"
+        );
+        assert_eq!(
+            part2("1 2\n\n3").unwrap_err().to_string(),
+            "line 2 '': expected some values"
+        );
+        assert_eq!(
+            part1("0 2000000000 -2000000000").unwrap_err().to_string(),
+            "line 1 '0 2000000000 -2000000000': the extrapolated value overflows"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+// Registry of all days, so the runner can drive them uniformly.
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+use std::hint::black_box;
+
+use crate::generate::Rng;
+use crate::{Answer, Error};
+
+/// A single part of a day, taking the puzzle input and returning the answer,
+/// or an error if the input is invalid.
+pub type Part = fn(&str) -> Result<Answer, Error>;
+
+/// What parts may return: an answer, or an answer from a fallible parse.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, Error>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer, Error> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>> IntoAnswer for Result<T, Error> {
+    fn into_answer(self) -> Result<Answer, Error> {
+        self.map(Into::into)
+    }
+}
+
+/// Parses an input the way a day's parts do and drops the result, so that
+/// parsing can be measured on its own.
+pub type Parse = fn(&str) -> Result<(), Error>;
+
+/// Generates a random input of roughly the given size, see `generate`.
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+pub struct Day {
+    pub number: u32,
+    pub parts: &'static [Part],
+    pub parse: Parse,
+    pub generate: Generator,
+}
+
+macro_rules! day {
+    ($number:literal, $module:ident) => {
+        day!($number, $module, $module::part1, $module::part2)
+    };
+    ($number:literal, $module:ident, $($part:expr),+) => {
+        Day {
+            number: $number,
+            parts: &[$(|input| IntoAnswer::into_answer($part(input))),+],
+            parse: |input| $module::parse(input).map(|parsed| {
+                black_box(parsed);
+            }),
+            generate: $module::generate,
+        }
+    };
+}
+
+pub static DAYS: [Day; 25] = [
+    day!(1, day1),
+    day!(2, day2),
+    day!(3, day3),
+    day!(4, day4),
+    day!(5, day5),
+    day!(6, day6),
+    day!(7, day7),
+    day!(8, day8),
+    day!(9, day9),
+    day!(10, day10),
+    day!(11, day11),
+    day!(12, day12),
+    day!(13, day13),
+    day!(14, day14),
+    day!(15, day15),
+    day!(16, day16),
+    day!(17, day17),
+    day!(18, day18),
+    day!(19, day19),
+    day!(20, day20),
+    day!(21, day21, |input| day21::part1(input, 64), |input| {
+        day21::part2(input, 26501365)
+    }),
+    day!(22, day22),
+    day!(23, day23),
+    day!(
+        24,
+        day24,
+        |input| day24::part1(input, day24::TEST_AREA),
+        day24::part2
+    ),
+    day!(25, day25, day25::part1),
+];
+
+pub fn get(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,75 @@
+use std::fmt::{self, Debug, Display};
+use std::ops::{Range, RangeInclusive};
+
+use crate::generate::generate;
+
+// Differential testing: some solvers rely on clever shortcuts, so they are
+// compared with slow but obviously correct reference solvers on many small
+// generated inputs.
+
+/// An input on which a solver and its reference disagree.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch<T> {
+    pub day: u32,
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    pub expected: T,
+    pub actual: T,
+}
+
+impl<T: Debug> Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} seed {} size {}: expected {:?}, got {:?} for input",
+            self.day, self.seed, self.size, self.expected, self.actual
+        )?;
+        write!(f, "{}", self.input)
+    }
+}
+
+/// Runs `solver` and `reference` on the inputs generated for `day` from every
+/// seed and size, smallest sizes first, and returns the first disagreement.
+pub fn compare<T: PartialEq + Debug>(
+    day: u32,
+    seeds: Range<u64>,
+    sizes: RangeInclusive<usize>,
+    solver: impl Fn(&str) -> T,
+    reference: impl Fn(&str) -> T,
+) -> Result<(), Mismatch<T>> {
+    for size in sizes {
+        for seed in seeds.clone() {
+            let input = generate(day, seed, size).expect("no such day");
+            let expected = reference(&input);
+            let actual = solver(&input);
+            if actual != expected {
+                return Err(Mismatch {
+                    day,
+                    seed,
+                    size,
+                    input,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let lines = |input: &str| input.lines().count();
+        assert_eq!(compare(1, 0..5, 1..=3, lines, lines), Ok(()));
+
+        // The generated day 1 input has `size` lines.
+        let mismatch = compare(1, 0..5, 1..=3, lines, |input| lines(input).min(2)).unwrap_err();
+        assert_eq!((mismatch.seed, mismatch.size), (0, 3));
+        assert_eq!((mismatch.expected, mismatch.actual), (2, 3));
+    }
+}
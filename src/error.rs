@@ -0,0 +1,174 @@
+use std::fmt::{self, Display};
+use std::io;
+use std::str::FromStr;
+
+// Errors of parsing a puzzle input. Parsers return them instead of panicking,
+// so that a bad input fails only its part, with the line it failed on.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+    line: Option<(usize, String)>,
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: None,
+        }
+    }
+
+    /// Adds the line the error happened on, numbered from 1, unless the
+    /// error already has one.
+    pub fn at_line(mut self, number: usize, text: &str) -> Self {
+        self.line.get_or_insert_with(|| (number, text.to_string()));
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line.as_ref().map(|(number, _)| *number)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.line {
+            Some((number, text)) => write!(f, "line {} '{}': {}", number, text, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Parses a trimmed value, like `crate::parse` but failing instead of panicking.
+pub fn parse<T: FromStr>(v: &str) -> Result<T>
+where
+    T::Err: Display,
+{
+    let v = v.trim();
+    v.parse()
+        .map_err(|error| Error::new(format!("cannot parse '{}': {}", v, error)))
+}
+
+/// Parses every line of the input, numbering the lines from `first`.
+pub fn parse_lines_from<T>(input: &str, first: usize) -> Result<Vec<T>>
+where
+    T: FromStr<Err = Error>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|error: Error| error.at_line(first + i, line))
+        })
+        .collect()
+}
+
+/// Parses every line of the input.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr<Err = Error>,
+{
+    parse_lines_from(input, 1)
+}
+
+/// Parses a grid of tiles, one row per line, all as wide as the first,
+/// numbering the lines from `first`.
+pub fn parse_grid_from<T>(
+    input: &str,
+    first: usize,
+    tile: impl Fn(char) -> Result<T>,
+) -> Result<Vec<Vec<T>>> {
+    let width = input.lines().next().map_or(0, |line| line.chars().count());
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let row = line.chars().map(&tile).collect::<Result<Vec<_>>>();
+            match row {
+                Ok(row) if row.len() != width => {
+                    Err(Error::new("the row is not as wide as the first"))
+                }
+                row => row,
+            }
+            .map_err(|error| error.at_line(first + i, line))
+        })
+        .collect()
+}
+
+/// Parses a grid of tiles, one row per line, all as wide as the first.
+pub fn parse_grid<T>(input: &str, tile: impl Fn(char) -> Result<T>) -> Result<Vec<Vec<T>>> {
+    parse_grid_from(input, 1, tile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Even(u32);
+
+    impl FromStr for Even {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            match parse(s)? {
+                n if n % 2 == 0 => Ok(Even(n)),
+                n => Err(Error::new(format!("{} is odd", n))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(parse_lines("2\n4"), Ok(vec![Even(2), Even(4)]));
+        let error = parse_lines::<Even>("2\n3\n5").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.to_string(), "line 2 '3': 3 is odd");
+        let error = parse_lines_from::<Even>("x", 7).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 7 'x': cannot parse 'x': invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_parse_grid() {
+        let digit = |c: char| c.to_digit(10).ok_or_else(|| Error::new("not a digit"));
+        assert_eq!(
+            parse_grid("12\n34", digit),
+            Ok(vec![vec![1, 2], vec![3, 4]])
+        );
+        let error = parse_grid("12\n3x", digit).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 '3x': not a digit");
+        let error = parse_grid("12\n345", digit).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2 '345': the row is not as wide as the first"
+        );
+        let error = parse_grid_from("x", 4, digit).unwrap_err();
+        assert_eq!(error.line(), Some(4));
+    }
+
+    #[test]
+    fn test_at_line_keeps_the_first_line() {
+        let error = Error::new("bad").at_line(3, "a").at_line(1, "b");
+        assert_eq!(error.line(), Some(3));
+        assert_eq!(error.message(), "bad");
+    }
+}
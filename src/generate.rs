@@ -0,0 +1,299 @@
+use std::collections::{BTreeSet, HashSet};
+use std::ops::Range;
+
+// Synthetic puzzle inputs. Real inputs may not be shared, so every day can
+// generate random inputs of a given size that satisfy the same assumptions
+// as the real ones. The generator is seeded and self-contained, so a seed
+// always produces the same input.
+
+/// A small SplitMix64 generator.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..n as i64) as usize
+    }
+
+    /// True with probability `p`, so never for 0 and always for 1.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// A random lowercase word of the given length.
+    pub fn word(&mut self, len: usize) -> String {
+        (0..len)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+
+    /// `count` distinct lowercase words of the given length, none of them in `reserved`.
+    pub fn words(&mut self, count: usize, len: usize, reserved: &[&str]) -> Vec<String> {
+        assert!(count + reserved.len() <= 26usize.pow(len as u32));
+        let mut seen: HashSet<String> = reserved.iter().map(|s| s.to_string()).collect();
+        let mut words = vec![];
+        while words.len() < count {
+            let word = self.word(len);
+            if seen.insert(word.clone()) {
+                words.push(word);
+            }
+        }
+        words
+    }
+}
+
+/// Generates an input for `day`, or `None` if there is no such day.
+pub fn generate(day: u32, seed: u64, size: usize) -> Option<String> {
+    let day = crate::days::get(day)?;
+    Some((day.generate)(&mut Rng::new(seed), size))
+}
+
+/// Traces the outline of a set of cells, as the list of corners of a closed
+/// rectilinear polygon. Cell `(x, y)` covers the square from corner `(x, y)`
+/// to corner `(x + 1, y + 1)`.
+///
+/// The cells must form a region without holes whose cells never touch only by
+/// a corner, as produced by `grow_region`. The outline goes clockwise
+/// (with y growing down) and starts at its top left corner.
+pub fn outline(region: &BTreeSet<(i64, i64)>) -> Vec<(i64, i64)> {
+    let inside = |x: i64, y: i64| region.contains(&(x, y));
+    let start = *region.iter().min_by_key(|(x, y)| (*y, *x)).unwrap();
+    // Walk along the boundary edges, keeping the region on the right.
+    let (mut x, mut y) = start;
+    let (mut dx, mut dy) = (1, 0);
+    let mut corners = vec![];
+    loop {
+        // The cells ahead-left and ahead-right of the walker at corner (x, y) going (dx, dy).
+        let ahead_left = inside(x + (dx + dy - 1) / 2, y + (dy - dx - 1) / 2);
+        let ahead_right = inside(x + (dx - dy - 1) / 2, y + (dy + dx - 1) / 2);
+        let (ndx, ndy) = match (ahead_left, ahead_right) {
+            (false, true) => (dx, dy),
+            (_, false) => (-dy, dx),
+            (true, true) => (dy, -dx),
+        };
+        if (ndx, ndy) != (dx, dy) || corners.is_empty() {
+            if corners.first() == Some(&(x, y)) {
+                break;
+            }
+            corners.push((x, y));
+        }
+        dx = ndx;
+        dy = ndy;
+        x += dx;
+        y += dy;
+    }
+    corners
+}
+
+/// Grows a random region of about `cells` cells inside a `width` by `height`
+/// grid, starting from `start`. The region never has holes, and its cells
+/// never touch only by a corner, so its outline is a simple polygon.
+pub fn grow_region(
+    rng: &mut Rng,
+    width: i64,
+    height: i64,
+    start: (i64, i64),
+    cells: usize,
+) -> BTreeSet<(i64, i64)> {
+    const RING: [(i64, i64); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+    let mut region = BTreeSet::from([start]);
+    // The same cells in insertion order, to pick from them deterministically.
+    let mut order = vec![start];
+    let mut failures = 0;
+    while region.len() < cells && failures < 100 * cells {
+        let (x, y) = *rng.choose(&order);
+        let (dx, dy) = *rng.choose(&[(0, -1), (1, 0), (0, 1), (-1, 0)]);
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx >= width || ny >= height {
+            failures += 1;
+            continue;
+        }
+        // Adding a cell keeps the region simple if its neighbours in the
+        // region form a single run around it.
+        let ring = RING.map(|(rx, ry)| region.contains(&(nx + rx, ny + ry)));
+        let runs = (0..8).filter(|i| ring[*i] && !ring[(i + 7) % 8]).count();
+        if region.contains(&(nx, ny)) || runs != 1 {
+            failures += 1;
+            continue;
+        }
+        region.insert((nx, ny));
+        order.push((nx, ny));
+    }
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::{self, DAYS};
+    use crate::runner::Runner;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let a = (0..10).map(|_| Rng::new(7).next_u64()).collect::<Vec<_>>();
+        assert!(a.iter().all(|n| *n == a[0]));
+        let mut rng = Rng::new(7);
+        assert_ne!(rng.next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((-3..5).contains(&rng.range(-3..5)));
+        }
+    }
+
+    #[test]
+    fn test_chance() {
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| !rng.chance(0.0)));
+        assert!((0..1000).all(|_| rng.chance(1.0)));
+    }
+
+    #[test]
+    fn test_outline() {
+        // An L shape.
+        let region = BTreeSet::from([(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(
+            outline(&region),
+            vec![(0, 0), (1, 0), (1, 1), (2, 1), (2, 2), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn test_grown_region_outline_is_simple() {
+        let mut rng = Rng::new(3);
+        for _ in 0..20 {
+            let region = grow_region(&mut rng, 12, 12, (6, 6), 60);
+            let corners = outline(&region);
+            // Walking the outline visits every boundary corner once.
+            let points: HashSet<_> = corners.iter().collect();
+            assert_eq!(points.len(), corners.len());
+            assert!(corners.len().is_multiple_of(2));
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_are_solved() {
+        // The answers for seed 1 and size 5. Those with reference solvers
+        // or answers known from the generator are also checked below.
+        const ANSWERS: [&[&str]; 25] = [
+            &["176", "168"],
+            &["9", "9460"],
+            &["185", "5800"],
+            &["11", "30"],
+            &["235793", "235793"],
+            &["1034880", "2787617714"],
+            &["7402", "7402"],
+            &["10", "150150"],
+            &["-650911", "-52"],
+            &["18", "31"],
+            &["10", "4000002"],
+            &["5", "5"],
+            &["2717", "1218"],
+            &["20", "17"],
+            &["591", "726"],
+            &["5", "5"],
+            &["19", "28"],
+            &["495", "10053400940"],
+            &["24388", "219529120000000"],
+            &["731331106", "12369"],
+            &["60", "690713763624291"],
+            &["4", "1"],
+            &["48", "66"],
+            &["1", "700880550141574"],
+            &["45"],
+        ];
+        let runner = Runner {
+            timeout: Duration::from_secs(60),
+            ..Runner::default()
+        };
+        for day in &DAYS {
+            let input: Arc<str> = Arc::from(generate(day.number, 1, 5).unwrap());
+            let answers = ANSWERS[day.number as usize - 1];
+            assert_eq!(day.parts.len(), answers.len(), "day {}", day.number);
+            for (i, (part, answer)) in day.parts.iter().zip(answers).enumerate() {
+                let report = runner.run_part(day.number, i + 1, *part, input.clone());
+                assert_eq!(
+                    report.result.as_ref().map(|answer| answer.to_string()).ok(),
+                    Some(answer.to_string()),
+                    "day {} part {}: {:?}",
+                    day.number,
+                    i + 1,
+                    report.result
+                );
+            }
+        }
+
+        let input = |day| generate(day, 1, 5).unwrap();
+        assert_eq!(
+            days::day5::part2_reference(&input(5)).to_string(),
+            ANSWERS[4][1]
+        );
+        assert_eq!(
+            days::day12::part1_reference(&input(12)).to_string(),
+            ANSWERS[11][0]
+        );
+        assert_eq!(
+            days::day19::part2_reference(&input(19))
+                .unwrap()
+                .to_string(),
+            ANSWERS[18][1]
+        );
+        // The rock is the first thing the day 24 generator draws.
+        let mut rng = Rng::new(1);
+        let rock: i64 = (0..3)
+            .map(|_| rng.range(200_000_000_000_000..400_000_000_000_000))
+            .sum();
+        assert_eq!(rock.to_string(), ANSWERS[23][1]);
+    }
+
+    #[test]
+    fn test_generated_inputs_are_parsed() {
+        for day in &DAYS {
+            let input = generate(day.number, 1, 5).unwrap();
+            assert_eq!((day.parse)(&input), Ok(()), "day {}", day.number);
+        }
+    }
+}
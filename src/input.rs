@@ -0,0 +1,308 @@
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Puzzle inputs are read from a local cache directory, and only fetched when
+// they are missing there. Fetched inputs are written to the cache, so every
+// input is requested at most once.
+
+pub const CONFIG_PATH: &str = "aoc.conf";
+const DEFAULT_URL: &str = "https://adventofcode.com/2023";
+const USER_AGENT: &str = "github.com/gabay/AoC2023 input fetcher";
+
+#[derive(Debug)]
+pub enum InputError {
+    Io { path: PathBuf, source: io::Error },
+    Fetch { day: u32, message: String },
+    Config { line: usize, message: String },
+    Missing { day: u32 },
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            InputError::Fetch { day, message } => {
+                write!(f, "cannot fetch input of day {}: {}", day, message)
+            }
+            InputError::Config { line, message } => {
+                write!(f, "{}:{}: {}", CONFIG_PATH, line, message)
+            }
+            InputError::Missing { day } => write!(
+                f,
+                "no input for day {} and no fetcher configured (see {})",
+                day, CONFIG_PATH
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// A source of puzzle inputs that are not in the cache yet.
+pub trait Fetcher {
+    fn fetch(&self, day: u32) -> Result<String, InputError>;
+}
+
+/// Reads inputs from a local mirror of the inputs directory, e.g. a shared drive.
+pub struct MirrorFetcher {
+    pub dir: PathBuf,
+}
+
+impl Fetcher for MirrorFetcher {
+    fn fetch(&self, day: u32) -> Result<String, InputError> {
+        let path = self.dir.join(day.to_string());
+        fs::read_to_string(&path).map_err(|source| InputError::Io { path, source })
+    }
+}
+
+/// Downloads inputs from `{url}/day/{day}/input`, authenticated with a session cookie.
+pub struct HttpFetcher {
+    pub url: String,
+    pub session: String,
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, day: u32) -> Result<String, InputError> {
+        let url = format!("{}/day/{}/input", self.url.trim_end_matches('/'), day);
+        let fetch_error = |message: String| InputError::Fetch { day, message };
+        ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|error| fetch_error(error.to_string()))?
+            .into_string()
+            .map_err(|error| fetch_error(error.to_string()))
+    }
+}
+
+pub struct InputProvider {
+    pub cache: PathBuf,
+    pub fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl Default for InputProvider {
+    fn default() -> Self {
+        Self {
+            cache: PathBuf::from("inputs"),
+            fetcher: None,
+        }
+    }
+}
+
+impl InputProvider {
+    pub fn from_config(config: Config) -> Self {
+        let fetcher: Option<Box<dyn Fetcher>> = match (config.mirror, config.session) {
+            (Some(dir), _) => Some(Box::new(MirrorFetcher { dir })),
+            (None, Some(session)) => Some(Box::new(HttpFetcher {
+                url: config.url,
+                session,
+            })),
+            (None, None) => None,
+        };
+        Self {
+            cache: config.cache,
+            fetcher,
+        }
+    }
+
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.cache.join(day.to_string())
+    }
+
+    pub fn get(&self, day: u32) -> Result<String, InputError> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(source) => return Err(InputError::Io { path, source }),
+        }
+
+        let fetcher = self.fetcher.as_ref().ok_or(InputError::Missing { day })?;
+        let input = fetcher.fetch(day)?;
+        fs::create_dir_all(&self.cache).map_err(|source| InputError::Io {
+            path: self.cache.clone(),
+            source,
+        })?;
+        fs::write(&path, &input).map_err(|source| InputError::Io { path, source })?;
+        Ok(input)
+    }
+}
+
+/// Settings read from `aoc.conf`, a file of `key = value` lines:
+///
+/// ```text
+/// # the session cookie of a logged in adventofcode.com user
+/// session = 53616c7465645f5f...
+/// # optional, instead of downloading copy inputs from this directory
+/// mirror = /mnt/shared/aoc2023
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub url: String,
+    pub cache: PathBuf,
+    pub mirror: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            url: DEFAULT_URL.to_string(),
+            cache: PathBuf::from("inputs"),
+            mirror: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config, falling back to the defaults if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, InputError> {
+        match fs::read_to_string(path) {
+            Ok(s) => Self::parse(&s),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(InputError::Io {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, InputError> {
+        let mut config = Self::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let config_error = |message: String| InputError::Config {
+                line: i + 1,
+                message,
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(format!("expected 'key = value', got '{}'", line)))?;
+            let value = value.trim().to_string();
+            match key.trim() {
+                "session" => config.session = Some(value),
+                "url" => config.url = value,
+                "cache" => config.cache = PathBuf::from(value),
+                "mirror" => config.mirror = Some(PathBuf::from(value)),
+                key => return Err(config_error(format!("unknown key '{}'", key))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::rc::Rc;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2023-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    struct CountingFetcher(Rc<Cell<usize>>);
+
+    impl Fetcher for CountingFetcher {
+        fn fetch(&self, day: u32) -> Result<String, InputError> {
+            self.0.set(self.0.get() + 1);
+            Ok(format!("input {}\n", day))
+        }
+    }
+
+    #[test]
+    fn test_fetched_inputs_are_cached() {
+        let fetches = Rc::new(Cell::new(0));
+        let cache = temp_dir("cache");
+        let provider = InputProvider {
+            cache: cache.clone(),
+            fetcher: Some(Box::new(CountingFetcher(fetches.clone()))),
+        };
+        assert_eq!(provider.get(3).unwrap(), "input 3\n");
+        assert_eq!(provider.get(3).unwrap(), "input 3\n");
+        assert_eq!(fetches.get(), 1);
+        assert_eq!(fs::read_to_string(cache.join("3")).unwrap(), "input 3\n");
+    }
+
+    #[test]
+    fn test_missing_fetcher() {
+        let provider = InputProvider {
+            cache: temp_dir("missing"),
+            fetcher: None,
+        };
+        assert!(matches!(
+            provider.get(1),
+            Err(InputError::Missing { day: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_mirror_fetcher() {
+        let mirror = temp_dir("mirror");
+        fs::write(mirror.join("7"), "32T3K 765").unwrap();
+        let fetcher = MirrorFetcher { dir: mirror };
+        assert_eq!(fetcher.fetch(7).unwrap(), "32T3K 765");
+        assert!(fetcher.fetch(8).is_err());
+    }
+
+    #[test]
+    fn test_http_fetcher() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2023", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect::<Vec<_>>();
+            let body = "1abc2\n";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+
+        let fetcher = HttpFetcher {
+            url,
+            session: "secret".to_string(),
+        };
+        assert_eq!(fetcher.fetch(1).unwrap(), "1abc2\n");
+        let request = server.join().unwrap();
+        assert_eq!(request[0], "GET /2023/day/1/input HTTP/1.1");
+        assert!(request.iter().any(|line| line == "Cookie: session=secret"));
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config::parse("# comment\nsession = abc\n\ncache=/tmp/inputs").unwrap();
+        assert_eq!(
+            config,
+            Config {
+                session: Some("abc".to_string()),
+                cache: PathBuf::from("/tmp/inputs"),
+                ..Config::default()
+            }
+        );
+        assert!(matches!(
+            Config::parse("session abc"),
+            Err(InputError::Config { line: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+// Names of things in an input (modules, nodes, workflows...) as small integer
+// ids, so that solvers can keep them in vectors instead of maps keyed by
+// strings. The names are borrowed from the input.
+
+/// Gives every name an id, counting from 0 in the order they are first seen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interner<'a> {
+    ids: HashMap<&'a str, usize>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of the name, new if the name was not seen before.
+    pub fn intern(&mut self, name: &'a str) -> usize {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.names.len() - 1
+        })
+    }
+
+    /// The id of a name that was already interned.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    /// All the names, by id.
+    pub fn names(&self) -> &[&'a str] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("broadcaster"), 0);
+        assert_eq!(names.intern("rx"), 1);
+        assert_eq!(names.intern("broadcaster"), 0);
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get("rx"), Some(1));
+        assert_eq!(names.get("button"), None);
+        assert_eq!(names.name(1), "rx");
+        assert_eq!(names.names(), ["broadcaster", "rx"]);
+    }
+}
@@ -3,6 +3,22 @@ use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
+pub mod answer;
+pub mod bitgrid;
+pub mod days;
+pub mod differential;
+pub mod error;
+pub mod generate;
+pub mod input;
+pub mod interner;
+pub mod log;
+pub mod render;
+pub mod runner;
+
+pub use answer::Answer;
+pub use bitgrid::BitGrid;
+pub use error::Error;
+
 // Common functionality for AoC
 
 pub fn hello_world() {
@@ -171,14 +187,154 @@ impl Direction {
     }
 }
 
-impl From<&str> for Direction {
-    fn from(value: &str) -> Self {
-        match value.chars().next().unwrap() {
-            'u' | 'U' => Direction::Up,
-            'd' | 'D' => Direction::Down,
-            'l' | 'L' => Direction::Left,
-            'r' | 'R' => Direction::Right,
-            _ => panic!("Cannot crete direction from '{}'", value),
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.chars().next() {
+            Some('u' | 'U') => Ok(Direction::Up),
+            Some('d' | 'D') => Ok(Direction::Down),
+            Some('l' | 'L') => Ok(Direction::Left),
+            Some('r' | 'R') => Ok(Direction::Right),
+            _ => Err(Error::new(format!("unknown direction '{}'", s))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+
+    // Properties checked on random values, one `Rng` seed per case, so a
+    // failing seed can be replayed.
+
+    const CASES: u64 = 500;
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+
+    fn cases(property: impl Fn(u64, &mut Rng)) {
+        for seed in 0..CASES {
+            property(seed, &mut Rng::new(seed));
         }
     }
+
+    fn point(rng: &mut Rng) -> Point {
+        Point::new(rng.range(-1000..1000) as i32, rng.range(-1000..1000) as i32)
+    }
+
+    /// A board of `cells` with between 1 and 10 rows of 1 to 10 cells each.
+    fn board_str(rng: &mut Rng, cells: &[char]) -> String {
+        let (width, height) = (1 + rng.below(10), 1 + rng.below(10));
+        (0..height)
+            .map(|_| (0..width).map(|_| *rng.choose(cells)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_steps_is_repeated_step() {
+        cases(|seed, rng| {
+            let (p, d, n) = (point(rng), *rng.choose(&DIRECTIONS), rng.below(50) as i32);
+            let stepped = (0..n).fold(p, |p, _| p.step(d));
+            assert_eq!(p.steps(d, n), stepped, "seed {}", seed);
+        });
+    }
+
+    #[test]
+    fn test_step_and_back() {
+        cases(|seed, rng| {
+            let (p, d) = (point(rng), *rng.choose(&DIRECTIONS));
+            assert_eq!(p.step(d).step(d.opposite()), p, "seed {}", seed);
+            let n = rng.range(-50..50) as i32;
+            assert_eq!(p.steps(d, n), p.steps(d.opposite(), -n), "seed {}", seed);
+            let manhattan = |a: Point, b: Point| (a.x - b.x).abs() + (a.y - b.y).abs();
+            assert_eq!(manhattan(p, p.steps(d, n)), n.abs(), "seed {}", seed);
+        });
+    }
+
+    #[test]
+    fn test_rotations() {
+        for d in DIRECTIONS {
+            assert_eq!(d.rotate_left().rotate_right(), d);
+            assert_eq!(d.rotate_right().rotate_left(), d);
+            assert_eq!(d.rotate_left().rotate_left(), d.opposite());
+            assert_eq!(d.rotate_right().rotate_right(), d.opposite());
+            assert_eq!(d.opposite().opposite(), d);
+            assert_ne!(d.rotate_left(), d);
+            let p = Point::new(0, 0);
+            // A quarter turn keeps the step length, but moves off its axis.
+            let (a, b) = (p.step(d), p.step(d.rotate_left()));
+            assert_eq!((a.x * b.x + a.y * b.y, a.x.abs() + a.y.abs()), (0, 1));
+        }
+    }
+
+    #[test]
+    fn test_direction_from_str() {
+        for d in DIRECTIONS {
+            let name = format!("{:?}", d);
+            assert_eq!(name.parse::<Direction>(), Ok(d));
+            assert_eq!(name[..1].to_lowercase().parse::<Direction>(), Ok(d));
+        }
+        assert!("".parse::<Direction>().is_err());
+        assert!("X".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_minmax() {
+        cases(|seed, rng| {
+            let (a, b) = (rng.range(-100..100), rng.range(-100..100));
+            let (lo, hi) = minmax(a, b);
+            assert!(lo <= hi, "seed {}", seed);
+            assert_eq!((lo, hi), (a.min(b), a.max(b)), "seed {}", seed);
+            assert_eq!(minmax(b, a), (lo, hi), "seed {}", seed);
+        });
+    }
+
+    #[test]
+    fn test_to_board_round_trip() {
+        cases(|seed, rng| {
+            let s = board_str(rng, &['.', '#', 'O', 'S']);
+            let board = to_board(&s);
+            let back = board
+                .iter()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert_eq!(back, s, "seed {}", seed);
+            for p in Point::find_multiple_in_board('#', &board) {
+                assert_eq!(p.get(&board), Some('#'), "seed {}", seed);
+            }
+        });
+    }
+
+    #[test]
+    fn test_to_num_board_round_trip() {
+        let digits = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+        cases(|seed, rng| {
+            let s = board_str(rng, &digits);
+            let board = to_num_board(&s);
+            let back = board
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|n| char::from_digit(*n as u32, 10).unwrap())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert_eq!(back, s, "seed {}", seed);
+            let chars = to_board(&s);
+            assert_eq!(
+                board.iter().map(Vec::len).collect::<Vec<_>>(),
+                chars.iter().map(Vec::len).collect::<Vec<_>>(),
+                "seed {}",
+                seed
+            );
+        });
+    }
 }
@@ -0,0 +1,216 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+// Diagnostics that solvers can leave in place, e.g. `crate::debug!("{:?}", goals)`.
+// Messages go to stderr, so answers on stdout stay clean, and are only written
+// if the filter enables their level for their target: the last part of the
+// module path, like `day20`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level '{}'", s)),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The most verbose level written for each target, or `None` if a target is off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub default: Option<Level>,
+    pub targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    pub const fn new(default: Option<Level>) -> Self {
+        Self {
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .rev()
+            .find(|(t, _)| t == target)
+            .map_or(self.default, |(_, max)| *max);
+        max.is_some_and(|max| level <= max)
+    }
+
+    fn max(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(_, max)| *max)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(Some(Level::Warn))
+    }
+}
+
+/// Parses comma-separated levels, each either for all targets or for a
+/// single one, like `info,day20=trace,day5=off`.
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let level = |s: &str| match s.trim() {
+            "off" => Ok(None),
+            s => s.parse().map(Some),
+        };
+        let mut filter = Filter::default();
+        for part in s.split(',').filter(|part| !part.trim().is_empty()) {
+            match part.split_once('=') {
+                Some((target, max)) => filter
+                    .targets
+                    .push((target.trim().to_string(), level(max)?)),
+                None => filter.default = level(part)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// The filter set by the `AOC_LOG` environment variable, or the default one.
+pub fn env_filter() -> Result<Filter, String> {
+    match std::env::var("AOC_LOG") {
+        Ok(spec) => spec.parse().map_err(|error| format!("AOC_LOG: {}", error)),
+        Err(_) => Ok(Filter::default()),
+    }
+}
+
+static FILTER: RwLock<Filter> = RwLock::new(Filter::new(Some(Level::Warn)));
+// The most verbose level of any target plus one, so that disabled messages
+// skip the lock.
+static MAX: AtomicU8 = AtomicU8::new(Level::Warn as u8 + 1);
+
+pub fn set_filter(filter: Filter) {
+    MAX.store(
+        filter.max().map_or(0, |max| max as u8 + 1),
+        Ordering::Relaxed,
+    );
+    *FILTER.write().unwrap() = filter;
+}
+
+pub fn enabled(target: &str, level: Level) -> bool {
+    (level as u8) < MAX.load(Ordering::Relaxed) && FILTER.read().unwrap().enabled(target, level)
+}
+
+pub fn write(target: &str, level: Level, message: fmt::Arguments) {
+    eprintln!("[{} {}] {}", level, target, message);
+}
+
+/// The target of a module path: its last part.
+pub fn target(module_path: &str) -> &str {
+    module_path.rsplit("::").next().unwrap_or(module_path)
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {{
+        let target = $crate::log::target(module_path!());
+        if $crate::log::enabled(target, $level) {
+            $crate::log::write(target, $level, format_args!($($arg)+));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_from_str() {
+        assert_eq!("".parse(), Ok(Filter::default()));
+        assert_eq!(
+            "debug, day20=trace,day5=off".parse(),
+            Ok(Filter {
+                default: Some(Level::Debug),
+                targets: vec![
+                    ("day20".to_string(), Some(Level::Trace)),
+                    ("day5".to_string(), None)
+                ],
+            })
+        );
+        assert!("loud".parse::<Filter>().is_err());
+        assert!("day5=loud".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_enabled() {
+        let filter: Filter = "info,day20=trace,day5=off".parse().unwrap();
+        assert!(filter.enabled("day1", Level::Info));
+        assert!(!filter.enabled("day1", Level::Debug));
+        assert!(filter.enabled("day20", Level::Trace));
+        assert!(!filter.enabled("day5", Level::Error));
+        assert_eq!(filter.max(), Some(Level::Trace));
+        assert_eq!(Filter::new(None).max(), None);
+    }
+
+    #[test]
+    fn test_target() {
+        assert_eq!(target("aoc2023::days::day20"), "day20");
+        assert_eq!(target("aoc2023"), "aoc2023");
+    }
+}
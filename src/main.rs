@@ -1,4 +1,263 @@
-fn main() {
-    println!("* Hello!");
-    println!("* In order to solve task N, please run 'cargo run --bin N'");
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use aoc2023::input::{Config, InputProvider, CONFIG_PATH};
+use aoc2023::log::{self, Level};
+use aoc2023::render::image::{Crop, ImageFile};
+use aoc2023::render::record::Recorder;
+use aoc2023::render::terminal::{Pace, Terminal};
+use aoc2023::render::{self, Canvas};
+use aoc2023::runner::{self, Runner};
+
+const USAGE: &str = "usage: aoc2023 [fetch] [-v...] [--timeout SECONDS] [--inputs DIR] [DAY...]
+       aoc2023 generate [--seed N] [--size N] [--out DIR] [DAY...]
+       aoc2023 show [--delay MILLISECONDS | --step] [--inputs DIR] DAY...
+       aoc2023 show --out FILE.{png,ppm,svg,dot,gif}|DIR [--scale PIXELS] [--crop X,Y,WIDTH,HEIGHT]
+                    [--fps N] [--inputs DIR] DAY";
+
+enum Command {
+    Run,
+    Fetch,
+    Generate,
+    Show,
+}
+
+struct Generate {
+    seed: u64,
+    size: usize,
+}
+
+struct Show {
+    pace: Pace,
+    scale: usize,
+    crop: Option<Crop>,
+    fps: f64,
+}
+
+fn main() -> ExitCode {
+    let config = match Config::load(Path::new(CONFIG_PATH)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut runner = Runner {
+        inputs: InputProvider::from_config(config),
+        ..Runner::default()
+    };
+    let mut generate = Generate { seed: 1, size: 100 };
+    let mut show = Show {
+        pace: Pace::Last,
+        scale: 4,
+        crop: None,
+        fps: 10.0,
+    };
+    let mut filter = match log::env_filter() {
+        Ok(filter) => filter,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut verbosity = 0;
+    let mut out: Option<PathBuf> = None;
+    let mut days = vec![];
+    let mut args = std::env::args().skip(1).peekable();
+    let command = match args.next_if(|arg| ["fetch", "generate", "show"].contains(&arg.as_str())) {
+        Some(arg) if arg == "fetch" => Command::Fetch,
+        Some(arg) if arg == "generate" => Command::Generate,
+        Some(_) => Command::Show,
+        None => Command::Run,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(seconds) if seconds > 0.0 => runner.timeout = Duration::from_secs_f64(seconds),
+                _ => return usage(),
+            },
+            "--inputs" => match args.next() {
+                Some(dir) => runner.inputs.cache = dir.into(),
+                None => return usage(),
+            },
+            "--seed" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(seed) => generate.seed = seed,
+                None => return usage(),
+            },
+            "--size" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(size) => generate.size = size,
+                None => return usage(),
+            },
+            "--out" => match args.next() {
+                Some(path) => out = Some(path.into()),
+                None => return usage(),
+            },
+            "--delay" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(millis) => show.pace = Pace::Delay(Duration::from_millis(millis)),
+                None => return usage(),
+            },
+            "--step" => show.pace = Pace::Step,
+            "-v" | "-vv" | "-vvv" => verbosity += arg.len() - 1,
+            "--scale" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(scale) if scale > 0 => show.scale = scale,
+                _ => return usage(),
+            },
+            "--crop" => match args.next().and_then(|s| s.parse().ok()) {
+                Some(crop) => show.crop = Some(crop),
+                None => return usage(),
+            },
+            "--fps" => match args.next().and_then(|s| s.parse::<f64>().ok()) {
+                Some(fps) if fps > 0.0 => show.fps = fps,
+                _ => return usage(),
+            },
+            _ => match arg.parse::<u32>() {
+                Ok(day) => days.push(day),
+                Err(_) => return usage(),
+            },
+        }
+    }
+    // -v, -vv and -vvv show info, debug and trace messages of every day.
+    let verbose = [
+        None,
+        Some(Level::Info),
+        Some(Level::Debug),
+        Some(Level::Trace),
+    ];
+    filter.default = filter.default.max(verbose[verbosity.min(3)]);
+    log::set_filter(filter);
+    if days.is_empty() {
+        if let Command::Show = command {
+            return usage();
+        }
+        days = aoc2023::days::DAYS.iter().map(|day| day.number).collect();
+    }
+    match command {
+        Command::Run => runner::run(&days, &runner),
+        Command::Fetch => fetch_inputs(&days, &runner.inputs),
+        // Several inputs on stdout could not be told apart.
+        Command::Generate if out.is_none() && days.len() != 1 => usage(),
+        Command::Generate => generate_inputs(&days, &generate, out.as_deref()),
+        // A single file cannot hold several days.
+        Command::Show if out.is_some() && days.len() != 1 => usage(),
+        Command::Show => show_days(&days, &runner.inputs, &show, out.as_deref()),
+    }
+}
+
+fn fetch_inputs(days: &[u32], inputs: &InputProvider) -> ExitCode {
+    let mut ok = true;
+    for day in days {
+        match inputs.get(*day) {
+            Ok(_) => println!("day {}: {}", day, inputs.path(*day).display()),
+            Err(error) => {
+                eprintln!("day {}: {}", day, error);
+                ok = false;
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn generate_inputs(days: &[u32], generate: &Generate, out: Option<&Path>) -> ExitCode {
+    let mut ok = true;
+    for day in days {
+        let Some(input) = aoc2023::generate::generate(*day, generate.seed, generate.size) else {
+            eprintln!("day {}: no such day", day);
+            ok = false;
+            continue;
+        };
+        let Some(out) = out else {
+            println!("{}", input);
+            continue;
+        };
+        let path = out.join(day.to_string());
+        match fs::create_dir_all(out).and_then(|_| fs::write(&path, input + "\n")) {
+            Ok(()) => println!("day {}: {}", day, path.display()),
+            Err(error) => {
+                eprintln!("day {}: {}: {}", day, path.display(), error);
+                ok = false;
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn show_days(days: &[u32], inputs: &InputProvider, show: &Show, out: Option<&Path>) -> ExitCode {
+    let mut ok = true;
+    for day in days {
+        let shown = inputs
+            .get(*day)
+            .map_err(|error| error.to_string())
+            .and_then(|input| show_day(*day, input.trim_matches('\n'), show, out));
+        match (shown, out) {
+            (Ok(()), Some(path)) => println!("day {}: {}", day, path.display()),
+            (Ok(()), None) => (),
+            (Err(error), _) => {
+                eprintln!("day {}: {}", day, error);
+                ok = false;
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn show_day(day: u32, input: &str, show: &Show, out: Option<&Path>) -> Result<(), String> {
+    let in_file = |error: io::Error| match out {
+        Some(path) => format!("{}: {}", path.display(), error),
+        None => error.to_string(),
+    };
+    let extension = out.and_then(|path| path.extension()?.to_str());
+    if let (Some(path), Some("svg")) = (out, extension) {
+        let drawing = render::drawing(day).ok_or("no drawing")?;
+        let drawing = drawing(input).map_err(|error| error.to_string())?;
+        let file = File::create(path).map_err(in_file)?;
+        return drawing.write(BufWriter::new(file)).map_err(in_file);
+    }
+    if let (Some(path), Some("dot")) = (out, extension) {
+        let graph = render::graph(day).ok_or("no graph")?;
+        let graph = graph(input).map_err(|error| error.to_string())?;
+        let file = File::create(path).map_err(in_file)?;
+        return graph.write(BufWriter::new(file)).map_err(in_file);
+    }
+    let visualize = render::visualizer(day).ok_or("no visualization")?;
+    let mut canvas: Box<dyn Canvas> = match (out, extension) {
+        (Some(path), Some("png" | "ppm")) => {
+            let image = ImageFile::new(path, show.scale);
+            Box::new(match show.crop {
+                Some(crop) => image.crop(crop),
+                None => image,
+            })
+        }
+        // Every frame, to a GIF or to a directory of PNGs.
+        (Some(path), _) => {
+            let recorder = Recorder::new(path).scale(show.scale).fps(show.fps);
+            Box::new(match show.crop {
+                Some(crop) => recorder.crop(crop),
+                None => recorder,
+            })
+        }
+        (None, _) => Box::new(Terminal::new(io::stdout().lock(), show.pace)),
+    };
+    visualize(input, canvas.as_mut())
+        .and_then(|_| canvas.finish())
+        .map_err(in_file)
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::FAILURE
 }
@@ -0,0 +1,176 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+use crate::render::Color;
+
+// Graphviz drawings of graph-shaped puzzles, e.g. `dot -Tsvg day20.dot`.
+// Nodes and edges are listed in the order they are added, so a day that adds
+// them in a fixed order always writes the same file.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Ellipse,
+    Box,
+    Diamond,
+    House,
+    DoubleCircle,
+}
+
+impl Shape {
+    fn name(&self) -> &'static str {
+        match self {
+            Shape::Ellipse => "ellipse",
+            Shape::Box => "box",
+            Shape::Diamond => "diamond",
+            Shape::House => "house",
+            Shape::DoubleCircle => "doublecircle",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Node {
+    pub shape: Shape,
+    pub fill: Option<Color>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Edge {
+    pub color: Option<Color>,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dot {
+    directed: bool,
+    lines: Vec<String>,
+}
+
+impl Dot {
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            lines: vec![],
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            lines: vec![],
+        }
+    }
+
+    pub fn node(&mut self, id: &str, node: Node) {
+        let mut attributes = vec![format!("shape={}", node.shape.name())];
+        if let Some(fill) = node.fill {
+            attributes.push(format!("fillcolor={}", quote(&hex(fill))));
+        }
+        if let Some(label) = &node.label {
+            attributes.push(format!("label={}", quote(label)));
+        }
+        self.lines
+            .push(format!("{} [{}]", quote(id), attributes.join(", ")));
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, edge: Edge) {
+        let mut attributes = vec![];
+        if let Some(color) = edge.color {
+            attributes.push(format!("color={}", quote(&hex(color))));
+        }
+        if let Some(label) = &edge.label {
+            attributes.push(format!("label={}", quote(label)));
+        }
+        let arrow = if self.directed { "->" } else { "--" };
+        let mut line = format!("{} {} {}", quote(from), arrow, quote(to));
+        if !attributes.is_empty() {
+            line += &format!(" [{}]", attributes.join(", "));
+        }
+        self.lines.push(line);
+    }
+
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "{}", self)?;
+        out.flush()
+    }
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        writeln!(f, "{} {{", kind)?;
+        writeln!(f, "  node [style=filled, fillcolor=\"#ffffff\"]")?;
+        for line in &self.lines {
+            writeln!(f, "  {}", line)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn hex(Color(r, g, b): Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directed() {
+        let mut dot = Dot::directed();
+        dot.node(
+            "in",
+            Node {
+                shape: Shape::Box,
+                fill: Some(Color::YELLOW),
+                ..Node::default()
+            },
+        );
+        dot.node(
+            "A",
+            Node {
+                label: Some("say \"yes\"".to_string()),
+                ..Node::default()
+            },
+        );
+        dot.edge(
+            "in",
+            "A",
+            Edge {
+                label: Some("x>10".to_string()),
+                ..Edge::default()
+            },
+        );
+        dot.edge("A", "in", Edge::default());
+        assert_eq!(
+            dot.to_string(),
+            r##"digraph {
+  node [style=filled, fillcolor="#ffffff"]
+  "in" [shape=box, fillcolor="#e6be28"]
+  "A" [shape=ellipse, label="say \"yes\""]
+  "in" -> "A" [label="x>10"]
+  "A" -> "in"
+}
+"##
+        );
+    }
+
+    #[test]
+    fn test_undirected() {
+        let mut dot = Dot::undirected();
+        let red = Edge {
+            color: Some(Color::RED),
+            ..Edge::default()
+        };
+        dot.edge("a", "b", red);
+        assert!(dot
+            .to_string()
+            .contains("  \"a\" -- \"b\" [color=\"#dc322f\"]\n"));
+    }
+}
@@ -0,0 +1,259 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::render::{Canvas, Cell, Color, Scene};
+
+// Raster images of scenes, for boards too large for the terminal. Every cell
+// becomes a square of pixels: its background colour, with its foreground
+// colour (e.g. a path) as a smaller square in the middle.
+
+const EMPTY: Color = Color(16, 16, 16);
+const FILLED: Color = Color(96, 96, 96);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl Image {
+    /// The scene with every cell drawn as a `scale` by `scale` square.
+    pub fn from_scene(scene: &Scene, scale: usize) -> Self {
+        let (width, height) = (scene.width() * scale, scene.height() * scale);
+        let mut pixels = vec![EMPTY; width * height];
+        let margin = scale / 4;
+        for (y, row) in scene.cells().iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let inner = (margin..scale - margin).contains(&dx)
+                            && (margin..scale - margin).contains(&dy);
+                        let color = match cell.fg {
+                            Some(fg) if inner => fg,
+                            _ => background(cell, scene.board[y][x]),
+                        };
+                        pixels[(y * scale + dy) * width + x * scale + dx] = color;
+                    }
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// The part of the image in the given rectangle of pixels, clipped to the image.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let (x, y) = (x.min(self.width), y.min(self.height));
+        let (width, height) = (width.min(self.width - x), height.min(self.height - y));
+        let pixels = (y..y + height)
+            .flat_map(|row| &self.pixels[row * self.width + x..row * self.width + x + width])
+            .copied()
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|Color(r, g, b)| [*r, *g, *b])
+            .collect()
+    }
+
+    /// Writes a binary PPM image, the simplest format most viewers open.
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.bytes())?;
+        out.flush()
+    }
+
+    pub fn write_png(&self, out: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.bytes())?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+// Overlays may replace the character, e.g. by an arrow, so the board's is used.
+fn background(cell: &Cell, ch: char) -> Color {
+    match (cell.bg, ch) {
+        (Some(bg), _) => bg,
+        (None, '.' | ' ') => EMPTY,
+        (None, _) => FILLED,
+    }
+}
+
+/// A rectangle of tiles, from its top left tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crop {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Parses `X,Y,WIDTH,HEIGHT`.
+impl FromStr for Crop {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers = s
+            .split(',')
+            .map(|n| n.trim().parse())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|error| format!("invalid crop '{}': {}", s, error))?;
+        match numbers[..] {
+            [x, y, width, height] => Ok(Crop {
+                x,
+                y,
+                width,
+                height,
+            }),
+            _ => Err(format!("invalid crop '{}': expected X,Y,WIDTH,HEIGHT", s)),
+        }
+    }
+}
+
+impl Crop {
+    /// The cropped part of an image of a scene drawn at `scale`.
+    pub fn apply(&self, image: &Image, scale: usize) -> Image {
+        image.crop(
+            self.x * scale,
+            self.y * scale,
+            self.width * scale,
+            self.height * scale,
+        )
+    }
+}
+
+/// Saves the last frame to a `.png` or `.ppm` file.
+pub struct ImageFile {
+    path: PathBuf,
+    scale: usize,
+    crop: Option<Crop>,
+    last: Option<Scene>,
+}
+
+impl ImageFile {
+    pub fn new(path: impl Into<PathBuf>, scale: usize) -> Self {
+        Self {
+            path: path.into(),
+            scale,
+            crop: None,
+            last: None,
+        }
+    }
+
+    pub fn crop(mut self, crop: Crop) -> Self {
+        self.crop = Some(crop);
+        self
+    }
+}
+
+impl Canvas for ImageFile {
+    fn draw(&mut self, scene: &Scene) -> io::Result<()> {
+        self.last = Some(scene.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let Some(scene) = self.last.take() else {
+            return Ok(());
+        };
+        let image = Image::from_scene(&scene, self.scale);
+        let image = match self.crop {
+            Some(crop) => crop.apply(&image, self.scale),
+            None => image,
+        };
+        let out = || File::create(&self.path).map(BufWriter::new);
+        match self.path.extension().and_then(|e| e.to_str()) {
+            Some("png") => image.write_png(out()?),
+            Some("ppm") => image.write_ppm(out()?),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "expected a .png or .ppm file",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_from_scene() {
+        let board = crate::to_board(".#");
+        let scene = Scene::new(&board).path([Point::new(0, 0), Point::new(1, 0)], Color::RED);
+        let image = Image::from_scene(&scene, 4);
+        assert_eq!((image.width, image.height), (8, 4));
+        // The path is drawn in the middle 2x2 pixels of its cells.
+        assert_eq!(image.pixels[0], EMPTY);
+        assert_eq!(image.pixels[4], FILLED);
+        assert_eq!(image.pixels[8 + 1], Color::RED);
+        assert_eq!(image.pixels[8 + 5], Color::RED);
+        assert_eq!(image.pixels[3 * 8 + 1], EMPTY);
+    }
+
+    #[test]
+    fn test_crop() {
+        let board = crate::to_board("#..\n.#.\n..#");
+        let image = Image::from_scene(&Scene::new(&board), 1);
+        let cropped = image.crop(1, 1, 5, 1);
+        assert_eq!((cropped.width, cropped.height), (2, 1));
+        assert_eq!(cropped.pixels, vec![FILLED, EMPTY]);
+        assert_eq!(image.crop(7, 0, 1, 1).pixels, vec![]);
+    }
+
+    #[test]
+    fn test_crop_from_str() {
+        assert_eq!(
+            "1,2,30,40".parse(),
+            Ok(Crop {
+                x: 1,
+                y: 2,
+                width: 30,
+                height: 40
+            })
+        );
+        assert!("1,2,3".parse::<Crop>().is_err());
+        assert!("1,2,3,x".parse::<Crop>().is_err());
+    }
+
+    #[test]
+    fn test_ppm() {
+        let board = crate::to_board("#");
+        let scene = Scene::new(&board).points([Point::new(0, 0)], Color::BLUE);
+        let mut out = vec![];
+        Image::from_scene(&scene, 1).write_ppm(&mut out).unwrap();
+        assert_eq!(out, b"P6\n1 1\n255\n\x28\x64\xc8");
+    }
+
+    #[test]
+    fn test_png() {
+        let image = Image::from_scene(&Scene::new(&crate::to_board("..\n.#")), 3);
+        let mut out = vec![];
+        image.write_png(&mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let decoder = png::Decoder::new(io::Cursor::new(out));
+        let mut reader = decoder.read_info().unwrap();
+        let mut bytes = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut bytes).unwrap();
+        assert_eq!(bytes, image.bytes());
+    }
+}
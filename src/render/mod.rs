@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::io;
+
+use crate::days::{
+    day10, day14, day16, day17, day18, day19, day20, day21, day22, day23, day25, day8,
+};
+use crate::Point;
+
+pub mod dot;
+pub mod image;
+pub mod record;
+pub mod svg;
+pub mod terminal;
+
+// Visualizations of grid puzzles. A day draws its board and whatever it wants
+// to highlight as a `Scene`, and hands one scene per step to a `Canvas`,
+// which shows or records them. Days whose shapes do not fit on a board can
+// make an `Svg` drawing instead, and days about networks a Graphviz `Dot` graph.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const GREY: Color = Color(110, 110, 110);
+    pub const RED: Color = Color(220, 50, 47);
+    pub const GREEN: Color = Color(80, 160, 60);
+    pub const YELLOW: Color = Color(230, 190, 40);
+    pub const BLUE: Color = Color(40, 100, 200);
+    pub const CYAN: Color = Color(40, 170, 180);
+
+    /// A colour from cold blue at 0 through yellow to hot red at 1.
+    pub fn heat(t: f64) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let (from, to, t) = if t < 0.5 {
+            (Color::BLUE, Color::YELLOW, t * 2.0)
+        } else {
+            (Color::YELLOW, Color::RED, t * 2.0 - 1.0)
+        };
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+/// Something drawn over the board.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Overlay {
+    /// Colours the background of every point.
+    Points { points: Vec<Point>, color: Color },
+    /// Draws an arrow from every point to the next one.
+    Path { points: Vec<Point>, color: Color },
+    /// Colours the background of every point by its value, from the lowest to the highest one.
+    Heatmap { values: HashMap<Point, f64> },
+}
+
+/// How a single cell of a scene looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// A board with overlays, drawn in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub board: Vec<Vec<char>>,
+    pub overlays: Vec<Overlay>,
+}
+
+impl Scene {
+    pub fn new(board: &[Vec<char>]) -> Self {
+        Self {
+            board: board.to_vec(),
+            overlays: vec![],
+        }
+    }
+
+    /// A scene of a board of single digits, as read by `to_num_board`.
+    pub fn from_digits(board: &[Vec<i32>]) -> Self {
+        let board: Vec<Vec<char>> = board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|n| char::from_digit(*n as u32, 10).unwrap_or('?'))
+                    .collect()
+            })
+            .collect();
+        Self::new(&board)
+    }
+
+    pub fn points(mut self, points: impl IntoIterator<Item = Point>, color: Color) -> Self {
+        let points = points.into_iter().collect();
+        self.overlays.push(Overlay::Points { points, color });
+        self
+    }
+
+    pub fn path(mut self, points: impl IntoIterator<Item = Point>, color: Color) -> Self {
+        let points = points.into_iter().collect();
+        self.overlays.push(Overlay::Path { points, color });
+        self
+    }
+
+    pub fn heatmap<T: Into<f64>>(mut self, values: impl IntoIterator<Item = (Point, T)>) -> Self {
+        let values = values.into_iter().map(|(p, v)| (p, v.into())).collect();
+        self.overlays.push(Overlay::Heatmap { values });
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.board.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.board.len()
+    }
+
+    /// Every cell of the board, with the overlays applied. Overlay points
+    /// outside of the board are ignored.
+    pub fn cells(&self) -> Vec<Vec<Cell>> {
+        let mut cells: Vec<Vec<Cell>> = self
+            .board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|ch| Cell {
+                        ch: *ch,
+                        fg: None,
+                        bg: None,
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut update = |p: &Point, f: &dyn Fn(&mut Cell)| {
+            if p.is_in_board(&cells) {
+                f(&mut cells[p.y as usize][p.x as usize]);
+            }
+        };
+        for overlay in &self.overlays {
+            match overlay {
+                Overlay::Points { points, color } => {
+                    for p in points {
+                        update(p, &|cell| cell.bg = Some(*color));
+                    }
+                }
+                Overlay::Path { points, color } => {
+                    for (i, p) in points.iter().enumerate() {
+                        let arrow = points.get(i + 1).and_then(|next| arrow(*p, *next));
+                        update(p, &|cell| {
+                            cell.ch = arrow.unwrap_or(cell.ch);
+                            cell.fg = Some(*color);
+                        });
+                    }
+                }
+                Overlay::Heatmap { values } => {
+                    let min = values.values().copied().fold(f64::INFINITY, f64::min);
+                    let max = values.values().copied().fold(f64::NEG_INFINITY, f64::max);
+                    for (p, value) in values {
+                        let t = if max > min {
+                            (value - min) / (max - min)
+                        } else {
+                            0.0
+                        };
+                        update(p, &|cell| cell.bg = Some(Color::heat(t)));
+                    }
+                }
+            }
+        }
+        cells
+    }
+}
+
+fn arrow(from: Point, to: Point) -> Option<char> {
+    match (to.x - from.x, to.y - from.y) {
+        (0, -1) => Some('^'),
+        (0, 1) => Some('v'),
+        (-1, 0) => Some('<'),
+        (1, 0) => Some('>'),
+        _ => None,
+    }
+}
+
+/// Shows or records the frames of a visualization.
+pub trait Canvas {
+    fn draw(&mut self, scene: &Scene) -> io::Result<()>;
+
+    /// Called once after the last frame.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps every frame, e.g. to check them in tests.
+impl Canvas for Vec<Scene> {
+    fn draw(&mut self, scene: &Scene) -> io::Result<()> {
+        self.push(scene.clone());
+        Ok(())
+    }
+}
+
+/// Hands scenes to a canvas from the step callbacks of a solver, which cannot
+/// fail: after an error it draws nothing more and keeps the error.
+pub struct Frames<'a> {
+    canvas: &'a mut dyn Canvas,
+    result: io::Result<()>,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(canvas: &'a mut dyn Canvas) -> Self {
+        Self {
+            canvas,
+            result: Ok(()),
+        }
+    }
+
+    /// Draws the scene that `scene` makes, unless drawing failed before.
+    pub fn draw(&mut self, scene: impl FnOnce() -> Scene) {
+        if self.result.is_ok() {
+            self.result = self.canvas.draw(&scene());
+        }
+    }
+
+    /// The first error, if drawing failed.
+    pub fn result(self) -> io::Result<()> {
+        self.result
+    }
+}
+
+/// Draws the frames of a day's visualization of the given input.
+pub type Visualizer = fn(&str, &mut dyn Canvas) -> io::Result<()>;
+
+/// The visualization of a day, if it has one.
+pub fn visualizer(day: u32) -> Option<Visualizer> {
+    match day {
+        10 => Some(day10::visualize),
+        14 => Some(day14::visualize),
+        16 => Some(day16::visualize),
+        17 => Some(day17::visualize),
+        21 => Some(day21::visualize),
+        22 => Some(day22::visualize),
+        23 => Some(day23::visualize),
+        _ => None,
+    }
+}
+
+/// Draws a day's polygons or paths from the given input.
+pub type Drawing = fn(&str) -> crate::error::Result<svg::Svg>;
+
+/// The vector drawing of a day, if it has one.
+pub fn drawing(day: u32) -> Option<Drawing> {
+    match day {
+        10 => Some(day10::drawing),
+        18 => Some(day18::drawing),
+        _ => None,
+    }
+}
+
+/// Builds the Graphviz graph of a day's network from the given input.
+pub type Graph = fn(&str) -> crate::error::Result<dot::Dot>;
+
+/// The graph of a day, if it has one.
+pub fn graph(day: u32) -> Option<Graph> {
+    match day {
+        8 => Some(day8::graph),
+        19 => Some(day19::graph),
+        20 => Some(day20::graph),
+        23 => Some(day23::graph),
+        25 => Some(day25::graph),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(ch: char, fg: Option<Color>, bg: Option<Color>) -> Cell {
+        Cell { ch, fg, bg }
+    }
+
+    #[test]
+    fn test_frames_stop_at_the_first_error() {
+        struct Failing(usize);
+        impl Canvas for Failing {
+            fn draw(&mut self, _: &Scene) -> io::Result<()> {
+                self.0 += 1;
+                Err(io::Error::other(format!("frame {}", self.0)))
+            }
+        }
+        let mut canvas = Failing(0);
+        let mut frames = Frames::new(&mut canvas);
+        let board = crate::to_board(".");
+        for _ in 0..3 {
+            frames.draw(|| Scene::new(&board));
+        }
+        assert_eq!(frames.result().unwrap_err().to_string(), "frame 1");
+        assert_eq!(canvas.0, 1);
+    }
+
+    #[test]
+    fn test_heat() {
+        assert_eq!(Color::heat(0.0), Color::BLUE);
+        assert_eq!(Color::heat(0.5), Color::YELLOW);
+        assert_eq!(Color::heat(1.0), Color::RED);
+        assert_eq!(Color::heat(7.0), Color::RED);
+    }
+
+    #[test]
+    fn test_cells() {
+        let board = crate::to_board("...\n.#.");
+        let scene = Scene::new(&board)
+            .points([Point::new(1, 1), Point::new(5, 5)], Color::GREY)
+            .path(
+                [Point::new(0, 1), Point::new(0, 0), Point::new(1, 0)],
+                Color::RED,
+            );
+        let red = Some(Color::RED);
+        assert_eq!(
+            scene.cells(),
+            vec![
+                vec![
+                    cell('>', red, None),
+                    cell('.', red, None),
+                    cell('.', None, None)
+                ],
+                vec![
+                    cell('^', red, None),
+                    cell('#', None, Some(Color::GREY)),
+                    cell('.', None, None)
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heatmap_is_scaled() {
+        let board = crate::to_board("...");
+        let scene = Scene::new(&board).heatmap([
+            (Point::new(0, 0), 10),
+            (Point::new(1, 0), 15),
+            (Point::new(2, 0), 20),
+        ]);
+        let colors = scene.cells()[0]
+            .iter()
+            .map(|c| c.bg.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(colors, vec![Color::BLUE, Color::YELLOW, Color::RED]);
+    }
+
+    #[test]
+    fn test_from_digits() {
+        let scene = Scene::from_digits(&crate::to_num_board("19\n05"));
+        assert_eq!(scene.board, crate::to_board("19\n05"));
+    }
+}
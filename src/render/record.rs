@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::render::image::{Crop, Image};
+use crate::render::{Canvas, Color, Scene};
+
+// Recordings of simulations, one frame per drawn scene. Frames are written as
+// they are drawn, as real inputs can take thousands of steps.
+
+/// Records every frame to an animated `.gif` file, or for any other path, to
+/// numbered `.png` files in that directory.
+pub struct Recorder {
+    path: PathBuf,
+    scale: usize,
+    fps: f64,
+    crop: Option<Crop>,
+    frames: usize,
+    gif: Option<gif::Encoder<BufWriter<File>>>,
+    // The size of the GIF, set by its first frame.
+    size: (u16, u16),
+}
+
+impl Recorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            scale: 4,
+            fps: 10.0,
+            crop: None,
+            frames: 0,
+            gif: None,
+            size: (0, 0),
+        }
+    }
+
+    /// Pixels per tile.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Frames per second of a GIF. GIFs count time in hundredths of a second,
+    /// so the rate is rounded to match.
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    pub fn crop(mut self, crop: Crop) -> Self {
+        self.crop = Some(crop);
+        self
+    }
+
+    fn write_gif_frame(&mut self, image: &Image) -> io::Result<()> {
+        let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "too large for a GIF");
+        let size = (
+            u16::try_from(image.width).map_err(too_large)?,
+            u16::try_from(image.height).map_err(too_large)?,
+        );
+        if self.gif.is_none() {
+            let file = BufWriter::new(File::create(&self.path)?);
+            let mut encoder =
+                gif::Encoder::new(file, size.0, size.1, &[]).map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+            self.gif = Some(encoder);
+            self.size = size;
+        }
+        if size != self.size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "all frames of a GIF must have the same size",
+            ));
+        }
+        let mut frame = gif_frame(image, size);
+        frame.delay = (100.0 / self.fps).round().clamp(1.0, u16::MAX as f64) as u16;
+        let encoder = self.gif.as_mut().unwrap();
+        encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
+
+fn gif_frame(image: &Image, (width, height): (u16, u16)) -> gif::Frame<'static> {
+    let mut indices = HashMap::new();
+    for pixel in &image.pixels {
+        let next = indices.len();
+        indices.entry(*pixel).or_insert(next);
+    }
+    // Only heatmaps have this many colours.
+    if indices.len() > 256 {
+        return gif::Frame::from_rgb_speed(width, height, &image.bytes(), 10);
+    }
+    let mut palette = vec![0; 3 * indices.len()];
+    for (Color(r, g, b), i) in &indices {
+        palette[3 * i..3 * i + 3].copy_from_slice(&[*r, *g, *b]);
+    }
+    let pixels = image
+        .pixels
+        .iter()
+        .map(|pixel| indices[pixel] as u8)
+        .collect::<Vec<_>>();
+    gif::Frame::from_palette_pixels(width, height, pixels, palette, None)
+}
+
+impl Canvas for Recorder {
+    fn draw(&mut self, scene: &Scene) -> io::Result<()> {
+        let image = Image::from_scene(scene, self.scale);
+        let image = match self.crop {
+            Some(crop) => crop.apply(&image, self.scale),
+            None => image,
+        };
+        if self.path.extension().is_some_and(|e| e == "gif") {
+            self.write_gif_frame(&image)?;
+        } else {
+            fs::create_dir_all(&self.path)?;
+            let path = self.path.join(format!("{:05}.png", self.frames));
+            image.write_png(BufWriter::new(File::create(path)?))?;
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(encoder) = self.gif.take() {
+            encoder.into_inner().map_err(io::Error::other)?.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("aoc2023-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&path);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn scenes() -> Vec<Scene> {
+        let board = crate::to_board("...\n.#.");
+        (0..3)
+            .map(|x| Scene::new(&board).points([Point::new(x, 0)], Color::RED))
+            .collect()
+    }
+
+    #[test]
+    fn test_gif() {
+        let path = temp_path("recording.gif");
+        let mut recorder = Recorder::new(&path).scale(2).fps(25.0).crop(Crop {
+            x: 1,
+            y: 0,
+            width: 2,
+            height: 2,
+        });
+        for scene in scenes() {
+            recorder.draw(&scene).unwrap();
+        }
+        recorder.finish().unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 4));
+        let mut frames = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 4);
+            frames.push(frame.buffer.to_vec());
+        }
+        assert_eq!(frames.len(), 3);
+        // The first frame's point is cropped away, the next ones are at the top left and right.
+        assert_ne!(&frames[0][..4], &[220, 50, 47, 255]);
+        assert_eq!(&frames[1][..4], &[220, 50, 47, 255]);
+        assert_eq!(&frames[2][12..16], &[220, 50, 47, 255]);
+    }
+
+    #[test]
+    fn test_frame_sequence() {
+        let dir = temp_path("recording");
+        let mut recorder = Recorder::new(&dir);
+        for scene in scenes() {
+            recorder.draw(&scene).unwrap();
+        }
+        recorder.finish().unwrap();
+        let mut files = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, vec!["00000.png", "00001.png", "00002.png"]);
+    }
+}
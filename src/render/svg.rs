@@ -0,0 +1,127 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::render::Color;
+
+// Vector drawings of polygons and paths, whose coordinates can be far too
+// large for a raster image (like day 18's real dig plan). Lines keep the same
+// width however much the drawing is scaled.
+
+const SIZE: f64 = 1000.0;
+
+/// A drawing of polygons and paths, in drawing order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Svg {
+    shapes: Vec<String>,
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Self {
+            shapes: vec![],
+            min: (f64::INFINITY, f64::INFINITY),
+            max: (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    pub fn polygon(
+        self,
+        points: impl IntoIterator<Item = (f64, f64)>,
+        fill: Color,
+        stroke: Color,
+    ) -> Self {
+        let style = format!("fill=\"{}\" stroke=\"{}\"", hex(fill), hex(stroke));
+        self.shape("polygon", points, &style)
+    }
+
+    pub fn polyline(self, points: impl IntoIterator<Item = (f64, f64)>, stroke: Color) -> Self {
+        let style = format!("fill=\"none\" stroke=\"{}\"", hex(stroke));
+        self.shape("polyline", points, &style)
+    }
+
+    fn shape(
+        mut self,
+        tag: &str,
+        points: impl IntoIterator<Item = (f64, f64)>,
+        style: &str,
+    ) -> Self {
+        let mut coordinates = String::new();
+        for (x, y) in points {
+            self.min = (self.min.0.min(x), self.min.1.min(y));
+            self.max = (self.max.0.max(x), self.max.1.max(y));
+            write!(coordinates, "{},{} ", x, y).unwrap();
+        }
+        self.shapes.push(format!(
+            "<{} points=\"{}\" {} stroke-width=\"2\" vector-effect=\"non-scaling-stroke\"/>",
+            tag,
+            coordinates.trim_end(),
+            style
+        ));
+        self
+    }
+
+    /// Writes the drawing, scaled so that its longest side is 1000 pixels.
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        let (min, max) = if self.shapes.is_empty() {
+            ((0.0, 0.0), (1.0, 1.0))
+        } else {
+            (self.min, self.max)
+        };
+        let margin = (max.0 - min.0).max(max.1 - min.1).max(1.0) / 50.0;
+        let (width, height) = (max.0 - min.0 + 2.0 * margin, max.1 - min.1 + 2.0 * margin);
+        let scale = SIZE / width.max(height);
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"{} {} {} {}\">",
+            width * scale,
+            height * scale,
+            min.0 - margin,
+            min.1 - margin,
+            width,
+            height
+        )?;
+        for shape in &self.shapes {
+            writeln!(out, "{}", shape)?;
+        }
+        writeln!(out, "</svg>")?;
+        out.flush()
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hex(Color(r, g, b): Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let svg = Svg::new()
+            .polygon(
+                [(0.0, 0.0), (50.0, 0.0), (50.0, 25.0)],
+                Color::BLUE,
+                Color::BLACK,
+            )
+            .polyline([(0.0, 0.0), (0.0, 25.0)], Color::RED);
+        let mut out = vec![];
+        svg.write(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1000\" height=\"519\" viewBox=\"-1 -1 52 27\">
+<polygon points=\"0,0 50,0 50,25\" fill=\"#2864c8\" stroke=\"#000000\" stroke-width=\"2\" vector-effect=\"non-scaling-stroke\"/>
+<polyline points=\"0,0 0,25\" fill=\"none\" stroke=\"#dc322f\" stroke-width=\"2\" vector-effect=\"non-scaling-stroke\"/>
+</svg>
+"
+        );
+    }
+}
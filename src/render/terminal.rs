@@ -0,0 +1,142 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::render::{Canvas, Color, Scene};
+
+// Scenes drawn with ANSI escape codes and 24-bit colours, which most
+// terminals support.
+
+const RESET: &str = "\x1b[0m";
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+/// The scene as text with colour codes, one line per row.
+pub fn render(scene: &Scene) -> String {
+    let mut s = String::new();
+    for row in scene.cells() {
+        let mut style = (None, None);
+        for cell in row {
+            if (cell.fg, cell.bg) != style {
+                if style != (None, None) {
+                    s.push_str(RESET);
+                }
+                style = (cell.fg, cell.bg);
+                push_style(&mut s, style);
+            }
+            s.push(cell.ch);
+        }
+        if style != (None, None) {
+            s.push_str(RESET);
+        }
+        s.push('\n');
+    }
+    s
+}
+
+fn push_style(s: &mut String, (fg, bg): (Option<Color>, Option<Color>)) {
+    if let Some(Color(r, g, b)) = fg {
+        write!(s, "\x1b[1;38;2;{};{};{}m", r, g, b).unwrap();
+    }
+    if let Some(Color(r, g, b)) = bg {
+        write!(s, "\x1b[48;2;{};{};{}m", r, g, b).unwrap();
+    }
+}
+
+/// When the terminal shows the frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pace {
+    /// Only the last frame, when finished.
+    Last,
+    /// Every frame, each one for the given time.
+    Delay(Duration),
+    /// Every frame, each one until Enter is pressed.
+    Step,
+}
+
+pub struct Terminal<W: Write> {
+    out: W,
+    pace: Pace,
+    frames: usize,
+    last: Option<String>,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, pace: Pace) -> Self {
+        Self {
+            out,
+            pace,
+            frames: 0,
+            last: None,
+        }
+    }
+}
+
+impl<W: Write> Canvas for Terminal<W> {
+    fn draw(&mut self, scene: &Scene) -> io::Result<()> {
+        self.frames += 1;
+        let frame = render(scene);
+        match self.pace {
+            Pace::Last => self.last = Some(frame),
+            Pace::Delay(delay) => {
+                writeln!(self.out, "{}{}frame {}", CLEAR, frame, self.frames)?;
+                self.out.flush()?;
+                thread::sleep(delay);
+            }
+            Pace::Step => {
+                write!(
+                    self.out,
+                    "{}{}frame {}, press Enter for the next one",
+                    CLEAR, frame, self.frames
+                )?;
+                self.out.flush()?;
+                io::stdin().read_line(&mut String::new())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if let Some(frame) = self.last.take() {
+            write!(self.out, "{}", frame)?;
+        }
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_render() {
+        let board = crate::to_board("..\n..");
+        let scene = Scene::new(&board).points([Point::new(1, 0)], Color::RED);
+        assert_eq!(render(&scene), ".\x1b[48;2;220;50;47m.\x1b[0m\n..\n");
+        assert_eq!(render(&Scene::new(&board)), "..\n..\n");
+    }
+
+    #[test]
+    fn test_last_frame() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, Pace::Last);
+        terminal.draw(&Scene::new(&crate::to_board("a"))).unwrap();
+        terminal.draw(&Scene::new(&crate::to_board("b"))).unwrap();
+        terminal.finish().unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "b\n");
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut out = vec![];
+        let mut terminal = Terminal::new(&mut out, Pace::Delay(Duration::ZERO));
+        terminal.draw(&Scene::new(&crate::to_board("a"))).unwrap();
+        terminal.draw(&Scene::new(&crate::to_board("b"))).unwrap();
+        terminal.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{}a\nframe 1\n{}b\nframe 2\n", CLEAR, CLEAR)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::panic;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::{mpsc, Arc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::days::{self, Day, Part};
+use crate::input::{Config, InputError, InputProvider, CONFIG_PATH};
+use crate::log;
+use crate::{Answer, Error};
+
+// Runs every part on its own thread, so a panicking or runaway solver
+// only fails that part instead of taking the whole run down with it.
+
+const THREAD_PREFIX: &str = "aoc-";
+// Some solvers recurse once per cell of the input.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+thread_local! {
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Input(InputError),
+    Invalid(Error),
+    Panicked { message: String },
+    TimedOut { limit: Duration },
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Input(error) => write!(f, "{}", error),
+            RunError::Invalid(error) => write!(f, "invalid input: {}", error),
+            RunError::Panicked { message } => write!(f, "panicked at {}", message),
+            RunError::TimedOut { limit } => write!(f, "timed out after {:?}", limit),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: usize,
+    pub elapsed: Duration,
+    pub result: Result<Answer, RunError>,
+}
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u32,
+    pub parts: Result<Vec<PartReport>, RunError>,
+}
+
+impl DayReport {
+    pub fn is_ok(&self) -> bool {
+        self.parts
+            .as_ref()
+            .is_ok_and(|parts| parts.iter().all(|part| part.result.is_ok()))
+    }
+}
+
+impl Display for DayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "day {}", self.day)?;
+        match &self.parts {
+            Ok(parts) => {
+                for part in parts {
+                    match &part.result {
+                        Ok(answer) => {
+                            writeln!(f, "  part{}: {} ({:.2?})", part.part, answer, part.elapsed)?
+                        }
+                        Err(error) => writeln!(f, "  part{}: error: {}", part.part, error)?,
+                    }
+                }
+                Ok(())
+            }
+            Err(error) => writeln!(f, "  error: {}", error),
+        }
+    }
+}
+
+pub struct Runner {
+    pub inputs: InputProvider,
+    pub timeout: Duration,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self {
+            inputs: InputProvider::default(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Runner {
+    pub fn run_day(&self, day: &Day) -> DayReport {
+        let parts = match self.inputs.get(day.number) {
+            Ok(input) => {
+                let input: Arc<str> = Arc::from(input.trim_matches('\n'));
+                Ok(day
+                    .parts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, part)| self.run_part(day.number, i + 1, *part, input.clone()))
+                    .collect())
+            }
+            Err(error) => Err(RunError::Input(error)),
+        };
+        DayReport {
+            day: day.number,
+            parts,
+        }
+    }
+
+    pub fn run_part(&self, day: u32, index: usize, part: Part, input: Arc<str>) -> PartReport {
+        install_panic_hook();
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name(format!("{}day{}-part{}", THREAD_PREFIX, day, index))
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let start = Instant::now();
+                let result = match panic::catch_unwind(|| part(&input)) {
+                    Ok(answer) => answer.map_err(RunError::Invalid),
+                    Err(payload) => {
+                        let message = PANIC_MESSAGE
+                            .with(|message| message.borrow_mut().take())
+                            .unwrap_or_else(|| payload_message(payload.as_ref()));
+                        Err(RunError::Panicked { message })
+                    }
+                };
+                // The receiver is gone if we already timed out.
+                let _ = sender.send((result, start.elapsed()));
+            });
+        if let Err(error) = spawned {
+            return PartReport {
+                part: index,
+                elapsed: Duration::ZERO,
+                result: Err(RunError::Panicked {
+                    message: format!("cannot spawn thread: {}", error),
+                }),
+            };
+        }
+
+        // A timed out thread cannot be stopped, it is left running in the background.
+        let (result, elapsed) = receiver.recv_timeout(self.timeout).unwrap_or((
+            Err(RunError::TimedOut {
+                limit: self.timeout,
+            }),
+            self.timeout,
+        ));
+        PartReport {
+            part: index,
+            elapsed,
+            result,
+        }
+    }
+}
+
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let is_runner_thread = thread::current()
+                .name()
+                .is_some_and(|name| name.starts_with(THREAD_PREFIX));
+            if is_runner_thread {
+                let location = info
+                    .location()
+                    .map(|location| location.to_string())
+                    .unwrap_or_else(|| "unknown location".to_string());
+                let message = format!("{}: {}", location, payload_message(info.payload()));
+                PANIC_MESSAGE.with(|m| *m.borrow_mut() = Some(message));
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs the given days and prints their reports, failing if any part failed.
+pub fn run(numbers: &[u32], runner: &Runner) -> ExitCode {
+    let mut ok = true;
+    for number in numbers {
+        match days::get(*number) {
+            Some(day) => {
+                let report = runner.run_day(day);
+                ok &= report.is_ok();
+                print!("{}", report);
+            }
+            None => {
+                eprintln!("day {}: no such day", number);
+                ok = false;
+            }
+        }
+    }
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// The input of a day, for the binaries that answer questions of their own
+/// about it. Like `main`, it sets the log filter from `AOC_LOG`.
+pub fn input(day: u32) -> Result<String, Box<dyn std::error::Error>> {
+    log::set_filter(log::env_filter()?);
+    let inputs = InputProvider::from_config(Config::load(Path::new(CONFIG_PATH))?);
+    Ok(inputs.get(day)?.trim_matches('\n').to_string())
+}
+
+/// Runs `query` on the input of a day, reporting its error if it fails.
+pub fn query(
+    day: u32,
+    query: impl FnOnce(&str) -> Result<(), Box<dyn std::error::Error>>,
+) -> ExitCode {
+    match input(day).and_then(|input| query(&input)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("day {}: {}", day, error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Entry point of the per-day binaries.
+pub fn main(number: u32) -> ExitCode {
+    match log::env_filter() {
+        Ok(filter) => log::set_filter(filter),
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+    match Config::load(Path::new(CONFIG_PATH)) {
+        Ok(config) => {
+            let runner = Runner {
+                inputs: InputProvider::from_config(config),
+                ..Runner::default()
+            };
+            run(&[number], &runner)
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_part(part: Part, timeout: Duration) -> PartReport {
+        let runner = Runner {
+            timeout,
+            ..Runner::default()
+        };
+        runner.run_part(0, 1, part, Arc::from("input"))
+    }
+
+    #[test]
+    fn test_answer() {
+        let report = run_part(|input| Ok(input.len().into()), Duration::from_secs(5));
+        assert_eq!(report.result.unwrap(), Answer::from(5usize));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let report = run_part(
+            |input| Err(Error::new("no digits").at_line(1, input)),
+            Duration::from_secs(5),
+        );
+        let Err(RunError::Invalid(error)) = report.result else {
+            panic!("expected an invalid input, got {:?}", report.result);
+        };
+        assert_eq!(error.line(), Some(1));
+    }
+
+    #[test]
+    fn test_panic() {
+        let report = run_part(|_| panic!("no solution..."), Duration::from_secs(5));
+        let Err(RunError::Panicked { message }) = report.result else {
+            panic!("expected a panic, got {:?}", report.result);
+        };
+        assert!(message.contains("runner.rs"));
+        assert!(message.ends_with("no solution..."));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let report = run_part(
+            |_| {
+                thread::sleep(Duration::from_secs(5));
+                Ok(Answer::from(0))
+            },
+            Duration::from_millis(10),
+        );
+        assert!(matches!(report.result, Err(RunError::TimedOut { .. })));
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let runner = Runner {
+            inputs: InputProvider {
+                cache: "no/such/dir".into(),
+                fetcher: None,
+            },
+            ..Runner::default()
+        };
+        let report = runner.run_day(days::get(1).unwrap());
+        assert!(matches!(report.parts, Err(RunError::Input(_))));
+        assert!(!report.is_ok());
+    }
+}